@@ -1,4 +1,5 @@
 //! Accounts structs for Raydium AMM / Liquidity.
+use crate::{keys::AmmKeys, pda::*};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::Token;
@@ -11,14 +12,14 @@ pub struct Initialize2<'info> {
         seeds = [
             crate::id().as_ref(),
             market.key.as_ref(),
-            b"amm_associated_seed",
+            AMM_ASSOCIATED_SEED,
         ],
         bump,
     )]
     pub amm: UncheckedAccount<'info>,
     /// CHECK: Safe. Amm authority, a PDA create with seed = [b"amm authority"]
     #[account(
-        seeds = [b"amm authority"],
+        seeds = [AUTHORITY_AMM],
         bump,
     )]
     pub amm_authority: UncheckedAccount<'info>,
@@ -28,7 +29,7 @@ pub struct Initialize2<'info> {
         seeds = [
             crate::id().as_ref(),
             market.key.as_ref(),
-            b"open_order_associated_seed",
+            OPEN_ORDER_ASSOCIATED_SEED,
         ],
         bump,
     )]
@@ -39,7 +40,7 @@ pub struct Initialize2<'info> {
         seeds = [
             crate::id().as_ref(),
             market.key.as_ref(),
-            b"lp_mint_associated_seed",
+            LP_MINT_ASSOCIATED_SEED,
         ],
         bump,
     )]
@@ -60,7 +61,7 @@ pub struct Initialize2<'info> {
         seeds = [
             crate::id().as_ref(),
             market.key.as_ref(),
-            b"coin_vault_associated_seed",
+            COIN_VAULT_ASSOCIATED_SEED,
         ],
         bump,
     )]
@@ -71,7 +72,7 @@ pub struct Initialize2<'info> {
         seeds = [
             crate::id().as_ref(),
             market.key.as_ref(),
-            b"pc_vault_associated_seed",
+            PC_VAULT_ASSOCIATED_SEED,
         ],
         bump,
     )]
@@ -82,14 +83,14 @@ pub struct Initialize2<'info> {
         seeds = [
             crate::id().as_ref(),
             market.key.as_ref(),
-            b"target_associated_seed",
+            TARGET_ASSOCIATED_SEED,
         ],
         bump,
     )]
     pub amm_target_orders: UncheckedAccount<'info>,
     /// CHECK: Safe. Amm Config.
     #[account(
-        seeds = [b"amm_config_account_seed"],
+        seeds = [AMM_CONFIG_SEED],
         bump,
     )]
     pub amm_config: UncheckedAccount<'info>,
@@ -153,7 +154,7 @@ pub struct Deposit<'info> {
     pub amm: UncheckedAccount<'info>,
     /// CHECK: Safe. Amm authority, a PDA create with seed = [b"amm authority"]
     #[account(
-        seeds = [b"amm authority"],
+        seeds = [AUTHORITY_AMM],
         bump,
     )]
     pub amm_authority: UncheckedAccount<'info>,
@@ -200,7 +201,7 @@ pub struct Withdraw<'info> {
     pub amm: UncheckedAccount<'info>,
     /// CHECK: Safe. Amm authority Account
     #[account(
-        seeds = [b"amm authority"],
+        seeds = [AUTHORITY_AMM],
         bump,
     )]
     pub amm_authority: UncheckedAccount<'info>,
@@ -255,6 +256,9 @@ pub struct Withdraw<'info> {
     pub market_asks: UncheckedAccount<'info>,
     /// CHECK: Safe. The spl token program
     pub token_program: Program<'info, Token>,
+    /// CHECK: Safe. Optional referrer pc token account, appended after the market accounts when set
+    #[account(mut)]
+    pub referrer_pc_account: Option<UncheckedAccount<'info>>,
 }
 
 /// Accounts for an `swap_base_in` instruction.
@@ -265,7 +269,7 @@ pub struct SwapBaseIn<'info> {
     pub amm: UncheckedAccount<'info>,
     /// CHECK: Safe. Amm authority Account
     #[account(
-        seeds = [b"amm authority"],
+        seeds = [AUTHORITY_AMM],
         bump,
     )]
     pub amm_authority: UncheckedAccount<'info>,
@@ -322,7 +326,428 @@ pub struct SwapBaseOut<'info> {
     pub amm: UncheckedAccount<'info>,
     /// CHECK: Safe. Amm authority Account
     #[account(
-        seeds = [b"amm authority"],
+        seeds = [AUTHORITY_AMM],
+        bump,
+    )]
+    pub amm_authority: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm open_orders Account
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_coin_vault Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub amm_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_pc_vault Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub amm_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook program id
+    pub market_program: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook market Account. OpenBook program is the owner.
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: Safe. bids Account
+    #[account(mut)]
+    pub market_bids: UncheckedAccount<'info>,
+    /// CHECK: Safe. asks Account
+    #[account(mut)]
+    pub market_asks: UncheckedAccount<'info>,
+    /// CHECK: Safe. event_q Account
+    #[account(mut)]
+    pub market_event_queue: UncheckedAccount<'info>,
+    /// CHECK: Safe. coin_vault Account
+    #[account(mut)]
+    pub market_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. pc_vault Account
+    #[account(mut)]
+    pub market_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. vault_signer Account
+    #[account(mut)]
+    pub market_vault_signer: UncheckedAccount<'info>,
+    /// CHECK: Safe. user source token Account. user Account to swap from.
+    #[account(mut)]
+    pub user_token_source: UncheckedAccount<'info>,
+    /// CHECK: Safe. user destination token Account. user Account to swap to.
+    #[account(mut)]
+    pub user_token_destination: UncheckedAccount<'info>,
+    /// CHECK: Safe. user owner Account
+    #[account(mut)]
+    pub user_source_owner: Signer<'info>,
+    /// CHECK: Safe. The spl token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Accounts for an `swap_base_in_v2` instruction.
+#[derive(Accounts, Clone)]
+pub struct SwapBaseInV2<'info> {
+    /// CHECK: Safe. amm Account
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+    /// CHECK: Safe. Amm authority Account
+    #[account(
+        seeds = [AUTHORITY_AMM],
+        bump,
+    )]
+    pub amm_authority: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_coin_vault Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub amm_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_pc_vault Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub amm_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. user source token Account. user Account to swap from.
+    #[account(mut)]
+    pub user_token_source: UncheckedAccount<'info>,
+    /// CHECK: Safe. user destination token Account. user Account to swap to.
+    #[account(mut)]
+    pub user_token_destination: UncheckedAccount<'info>,
+    /// CHECK: Safe. user owner Account
+    #[account(mut)]
+    pub user_source_owner: Signer<'info>,
+    /// CHECK: Safe. The spl token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Accounts for an `swap_base_out_v2` instruction.
+#[derive(Accounts, Clone)]
+pub struct SwapBaseOutV2<'info> {
+    /// CHECK: Safe. amm Account
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+    /// CHECK: Safe. Amm authority Account
+    #[account(
+        seeds = [AUTHORITY_AMM],
+        bump,
+    )]
+    pub amm_authority: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_coin_vault Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub amm_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_pc_vault Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub amm_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. user source token Account. user Account to swap from.
+    #[account(mut)]
+    pub user_token_source: UncheckedAccount<'info>,
+    /// CHECK: Safe. user destination token Account. user Account to swap to.
+    #[account(mut)]
+    pub user_token_destination: UncheckedAccount<'info>,
+    /// CHECK: Safe. user owner Account
+    #[account(mut)]
+    pub user_source_owner: Signer<'info>,
+    /// CHECK: Safe. The spl token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Accounts for an `deposit` instruction, with the pool accounts validated
+/// against a single decode of the `AmmInfo`. Convert into `Deposit` to invoke it.
+#[derive(Accounts, Clone)]
+pub struct CheckedDeposit<'info> {
+    /// CHECK: Safe. Amm Account
+    #[account(
+        mut,
+        owner = crate::id(),
+        constraint = AmmKeys::validate(
+            &amm,
+            amm_open_orders.key,
+            Some(amm_target_orders.key),
+            Some(amm_lp_mint.key),
+            amm_coin_vault.key,
+            amm_pc_vault.key,
+            market.key,
+        ).map(|()| true)?,
+    )]
+    pub amm: UncheckedAccount<'info>,
+    /// CHECK: Safe. Amm authority, a PDA create with seed = [b"amm authority"]
+    #[account(
+        seeds = [AUTHORITY_AMM],
+        bump,
+    )]
+    pub amm_authority: UncheckedAccount<'info>,
+    /// CHECK: Safe. AMM open_orders Account.
+    pub amm_open_orders: UncheckedAccount<'info>,
+    /// CHECK: Safe. AMM target orders account. To store plan orders infomations.
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+    /// CHECK: Safe. LP mint account. Must be empty, owned by $authority.
+    #[account(mut)]
+    pub amm_lp_mint: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_coin_vault account, $authority can transfer amount.
+    #[account(mut)]
+    pub amm_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_pc_vault account, $authority can transfer amount.
+    #[account(mut)]
+    pub amm_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook market account, OpenBook program is the owner.
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook market event queue account, OpenBook program is the owner.
+    pub market_event_queue: UncheckedAccount<'info>,
+    /// CHECK: Safe. User token coin to deposit into.
+    #[account(mut)]
+    pub user_token_coin: UncheckedAccount<'info>,
+    /// CHECK: Safe. User token pc to deposit into.
+    #[account(mut)]
+    pub user_token_pc: UncheckedAccount<'info>,
+    /// CHECK: Safe. User lp token, to deposit the generated tokens, user is the owner
+    #[account(mut)]
+    pub user_token_lp: UncheckedAccount<'info>,
+    /// CHECK: Safe. User wallet account
+    #[account(mut)]
+    pub user_owner: Signer<'info>,
+    /// CHECK: Safe. The spl token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> From<CheckedDeposit<'info>> for Deposit<'info> {
+    fn from(accounts: CheckedDeposit<'info>) -> Self {
+        Self {
+            amm: accounts.amm,
+            amm_authority: accounts.amm_authority,
+            amm_open_orders: accounts.amm_open_orders,
+            amm_target_orders: accounts.amm_target_orders,
+            amm_lp_mint: accounts.amm_lp_mint,
+            amm_coin_vault: accounts.amm_coin_vault,
+            amm_pc_vault: accounts.amm_pc_vault,
+            market: accounts.market,
+            market_event_queue: accounts.market_event_queue,
+            user_token_coin: accounts.user_token_coin,
+            user_token_pc: accounts.user_token_pc,
+            user_token_lp: accounts.user_token_lp,
+            user_owner: accounts.user_owner,
+            token_program: accounts.token_program,
+        }
+    }
+}
+
+/// Accounts for an `withdraw` instruction, with the pool accounts validated
+/// against a single decode of the `AmmInfo`. Convert into `Withdraw` to invoke it.
+#[derive(Accounts, Clone)]
+pub struct CheckedWithdraw<'info> {
+    /// CHECK: Safe. Amm account
+    #[account(
+        mut,
+        owner = crate::id(),
+        constraint = AmmKeys::validate(
+            &amm,
+            amm_open_orders.key,
+            Some(amm_target_orders.key),
+            Some(amm_lp_mint.key),
+            amm_coin_vault.key,
+            amm_pc_vault.key,
+            market.key,
+        ).map(|()| true)?,
+    )]
+    pub amm: UncheckedAccount<'info>,
+    /// CHECK: Safe. Amm authority Account
+    #[account(
+        seeds = [AUTHORITY_AMM],
+        bump,
+    )]
+    pub amm_authority: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm open_orders Account
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm target_orders Account. To store plan orders infomations.
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+    /// CHECK: Safe. pool lp mint account. Must be empty, owned by $authority.
+    #[account(mut)]
+    pub amm_lp_mint: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_coin_vault Amm Account to withdraw FROM,
+    #[account(mut)]
+    pub amm_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_pc_vault Amm Account to withdraw FROM,
+    #[account(mut)]
+    pub amm_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook program id
+    #[account(
+        address = crate::openbook_program_id::id(),
+    )]
+    pub market_program: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook market Account. OpenBook program is the owner.
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook coin_vault Account
+    #[account(mut)]
+    pub market_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook pc_vault Account
+    #[account(mut)]
+    pub market_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook vault_signer Account
+    pub market_vault_signer: UncheckedAccount<'info>,
+    /// CHECK: Safe. user lp token Account. Source lp, amount is transferable by $authority.
+    #[account(mut)]
+    pub user_token_lp: UncheckedAccount<'info>,
+    /// CHECK: Safe. user token coin Account. user Account to credit.
+    #[account(mut)]
+    pub user_token_coin: UncheckedAccount<'info>,
+    /// CHECK: Safe. user token pc Account. user Account to credit.
+    #[account(mut)]
+    pub user_token_pc: UncheckedAccount<'info>,
+    /// CHECK: Safe. User wallet account
+    #[account(mut)]
+    pub user_owner: Signer<'info>,
+    /// CHECK: Safe. OpenBook event queue account
+    #[account(mut)]
+    pub market_event_q: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook bid account
+    #[account(mut)]
+    pub market_bids: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook ask account
+    #[account(mut)]
+    pub market_asks: UncheckedAccount<'info>,
+    /// CHECK: Safe. The spl token program
+    pub token_program: Program<'info, Token>,
+    /// CHECK: Safe. Optional referrer pc token account, appended after the market accounts when set
+    #[account(mut)]
+    pub referrer_pc_account: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> From<CheckedWithdraw<'info>> for Withdraw<'info> {
+    fn from(accounts: CheckedWithdraw<'info>) -> Self {
+        Self {
+            amm: accounts.amm,
+            amm_authority: accounts.amm_authority,
+            amm_open_orders: accounts.amm_open_orders,
+            amm_target_orders: accounts.amm_target_orders,
+            amm_lp_mint: accounts.amm_lp_mint,
+            amm_coin_vault: accounts.amm_coin_vault,
+            amm_pc_vault: accounts.amm_pc_vault,
+            market_program: accounts.market_program,
+            market: accounts.market,
+            market_coin_vault: accounts.market_coin_vault,
+            market_pc_vault: accounts.market_pc_vault,
+            market_vault_signer: accounts.market_vault_signer,
+            user_token_lp: accounts.user_token_lp,
+            user_token_coin: accounts.user_token_coin,
+            user_token_pc: accounts.user_token_pc,
+            user_owner: accounts.user_owner,
+            market_event_q: accounts.market_event_q,
+            market_bids: accounts.market_bids,
+            market_asks: accounts.market_asks,
+            token_program: accounts.token_program,
+            referrer_pc_account: accounts.referrer_pc_account,
+        }
+    }
+}
+
+/// Accounts for an `swap_base_in` instruction, with the pool accounts validated
+/// against a single decode of the `AmmInfo`. Convert into `SwapBaseIn` to invoke it.
+#[derive(Accounts, Clone)]
+pub struct CheckedSwapBaseIn<'info> {
+    /// CHECK: Safe. amm Account
+    #[account(
+        mut,
+        owner = crate::id(),
+        constraint = AmmKeys::validate(
+            &amm,
+            amm_open_orders.key,
+            None,
+            None,
+            amm_coin_vault.key,
+            amm_pc_vault.key,
+            market.key,
+        ).map(|()| true)?,
+    )]
+    pub amm: UncheckedAccount<'info>,
+    /// CHECK: Safe. Amm authority Account
+    #[account(
+        seeds = [AUTHORITY_AMM],
+        bump,
+    )]
+    pub amm_authority: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm open_orders Account
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_coin_vault Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub amm_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. amm_pc_vault Amm Account to swap FROM or To,
+    #[account(mut)]
+    pub amm_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe.OpenBook program id
+    #[account(
+        address = crate::openbook_program_id::id(),
+    )]
+    pub market_program: UncheckedAccount<'info>,
+    /// CHECK: Safe. OpenBook market Account. OpenBook program is the owner.
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+    /// CHECK: Safe. bids Account
+    #[account(mut)]
+    pub market_bids: UncheckedAccount<'info>,
+    /// CHECK: Safe. asks Account
+    #[account(mut)]
+    pub market_asks: UncheckedAccount<'info>,
+    /// CHECK: Safe. event_q Account
+    #[account(mut)]
+    pub market_event_queue: UncheckedAccount<'info>,
+    /// CHECK: Safe. coin_vault Account
+    #[account(mut)]
+    pub market_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. pc_vault Account
+    #[account(mut)]
+    pub market_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Safe. vault_signer Account
+    #[account(mut)]
+    pub market_vault_signer: UncheckedAccount<'info>,
+    /// CHECK: Safe. user source token Account. user Account to swap from.
+    #[account(mut)]
+    pub user_token_source: UncheckedAccount<'info>,
+    /// CHECK: Safe. user destination token Account. user Account to swap to.
+    #[account(mut)]
+    pub user_token_destination: UncheckedAccount<'info>,
+    /// CHECK: Safe. user owner Account
+    #[account(mut)]
+    pub user_source_owner: Signer<'info>,
+    /// CHECK: Safe. The spl token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> From<CheckedSwapBaseIn<'info>> for SwapBaseIn<'info> {
+    fn from(accounts: CheckedSwapBaseIn<'info>) -> Self {
+        Self {
+            amm: accounts.amm,
+            amm_authority: accounts.amm_authority,
+            amm_open_orders: accounts.amm_open_orders,
+            amm_coin_vault: accounts.amm_coin_vault,
+            amm_pc_vault: accounts.amm_pc_vault,
+            market_program: accounts.market_program,
+            market: accounts.market,
+            market_bids: accounts.market_bids,
+            market_asks: accounts.market_asks,
+            market_event_queue: accounts.market_event_queue,
+            market_coin_vault: accounts.market_coin_vault,
+            market_pc_vault: accounts.market_pc_vault,
+            market_vault_signer: accounts.market_vault_signer,
+            user_token_source: accounts.user_token_source,
+            user_token_destination: accounts.user_token_destination,
+            user_source_owner: accounts.user_source_owner,
+            token_program: accounts.token_program,
+        }
+    }
+}
+
+/// Accounts for an `swap_base_out` instruction, with the pool accounts validated
+/// against a single decode of the `AmmInfo`. Convert into `SwapBaseOut` to invoke it.
+#[derive(Accounts, Clone)]
+pub struct CheckedSwapBaseOut<'info> {
+    /// CHECK: Safe. amm Account
+    #[account(
+        mut,
+        owner = crate::id(),
+        constraint = AmmKeys::validate(
+            &amm,
+            amm_open_orders.key,
+            None,
+            None,
+            amm_coin_vault.key,
+            amm_pc_vault.key,
+            market.key,
+        ).map(|()| true)?,
+    )]
+    pub amm: UncheckedAccount<'info>,
+    /// CHECK: Safe. Amm authority Account
+    #[account(
+        seeds = [AUTHORITY_AMM],
         bump,
     )]
     pub amm_authority: UncheckedAccount<'info>,
@@ -336,6 +761,9 @@ pub struct SwapBaseOut<'info> {
     #[account(mut)]
     pub amm_pc_vault: UncheckedAccount<'info>,
     /// CHECK: Safe. OpenBook program id
+    #[account(
+        address = crate::openbook_program_id::id(),
+    )]
     pub market_program: UncheckedAccount<'info>,
     /// CHECK: Safe. OpenBook market Account. OpenBook program is the owner.
     #[account(mut)]
@@ -370,3 +798,27 @@ pub struct SwapBaseOut<'info> {
     /// CHECK: Safe. The spl token program
     pub token_program: Program<'info, Token>,
 }
+
+impl<'info> From<CheckedSwapBaseOut<'info>> for SwapBaseOut<'info> {
+    fn from(accounts: CheckedSwapBaseOut<'info>) -> Self {
+        Self {
+            amm: accounts.amm,
+            amm_authority: accounts.amm_authority,
+            amm_open_orders: accounts.amm_open_orders,
+            amm_coin_vault: accounts.amm_coin_vault,
+            amm_pc_vault: accounts.amm_pc_vault,
+            market_program: accounts.market_program,
+            market: accounts.market,
+            market_bids: accounts.market_bids,
+            market_asks: accounts.market_asks,
+            market_event_queue: accounts.market_event_queue,
+            market_coin_vault: accounts.market_coin_vault,
+            market_pc_vault: accounts.market_pc_vault,
+            market_vault_signer: accounts.market_vault_signer,
+            user_token_source: accounts.user_token_source,
+            user_token_destination: accounts.user_token_destination,
+            user_source_owner: accounts.user_source_owner,
+            token_program: accounts.token_program,
+        }
+    }
+}
@@ -59,9 +59,12 @@ pub fn initialize<'a, 'b, 'c, 'info>(
 ///
 /// See [library::native_instrcutions::DepositInstruction].
 ///
-/// * `max_coin_amount` - Pool token amount to transfer. token_a and token_b amount are set by the current exchange rate and size of the pool
-/// * `max_pc_amount` - The effective time.
-/// * `base_side` - .
+/// * `max_coin_amount` - Max coin amount to deposit, exact if `base_side` is coin.
+/// * `max_pc_amount` - Max pc amount to deposit, exact if `base_side` is pc.
+/// * `base_side` - The fixed side, 0 for coin and 1 for pc, the other side is computed
+///   from the pool ratio. `u64::from(BaseSide::Pc)` converts a [library::BaseSide].
+///
+/// [quote::deposit_amounts] computes the amounts from the pool reserves.
 pub fn deposit<'a, 'b, 'c, 'info>(
     ctx: CpiContext<'a, 'b, 'c, 'info, Deposit<'info>>,
     max_coin_amount: u64,
@@ -95,6 +98,51 @@ pub fn deposit<'a, 'b, 'c, 'info>(
     Ok(())
 }
 
+/// Creates and invokes a [library::native_instrcutions::deposit_with_min] instruction.
+///
+/// # Arguments
+///
+/// See [library::native_instrcutions::DepositInstruction].
+///
+/// * `max_coin_amount` - Max coin amount to deposit, exact if `base_side` is coin.
+/// * `max_pc_amount` - Max pc amount to deposit, exact if `base_side` is pc.
+/// * `base_side` - The fixed side, 0 for coin and 1 for pc, see [library::BaseSide].
+/// * `other_amount_min` - Minimum amount of the other side to deposit, prevents excessive slippage.
+pub fn deposit_with_min<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, Deposit<'info>>,
+    max_coin_amount: u64,
+    max_pc_amount: u64,
+    base_side: u64,
+    other_amount_min: u64,
+) -> Result<()> {
+    let ix = native_instrcutions::deposit_with_min(
+        ctx.program.key,
+        ctx.accounts.amm.key,
+        ctx.accounts.amm_authority.key,
+        ctx.accounts.amm_open_orders.key,
+        ctx.accounts.amm_target_orders.key,
+        ctx.accounts.amm_lp_mint.key,
+        ctx.accounts.amm_coin_vault.key,
+        ctx.accounts.amm_pc_vault.key,
+        ctx.accounts.market.key,
+        ctx.accounts.market_event_queue.key,
+        ctx.accounts.user_token_coin.key,
+        ctx.accounts.user_token_pc.key,
+        ctx.accounts.user_token_lp.key,
+        ctx.accounts.user_owner.key,
+        max_coin_amount,
+        max_pc_amount,
+        base_side,
+        other_amount_min,
+    )?;
+    solana_program::program::invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
 /// Creates and invokes a [library::native_instrcutions::withdraw] instruction.
 ///
 /// # Arguments
@@ -103,7 +151,8 @@ pub fn deposit<'a, 'b, 'c, 'info>(
 ///
 /// * `amount` - Pool token amount to transfer. token_a and token_b amount are set by
 ///             the current exchange rate and size of the pool.
-
+///
+/// `referrer_pc_account` of the context is passed as the optional trailing account if set.
 pub fn withdraw<'a, 'b, 'c, 'info>(
     ctx: CpiContext<'a, 'b, 'c, 'info, Withdraw<'info>>,
     amount: u64,
@@ -129,8 +178,65 @@ pub fn withdraw<'a, 'b, 'c, 'info>(
         ctx.accounts.market_event_q.key,
         ctx.accounts.market_bids.key,
         ctx.accounts.market_asks.key,
-        None,
+        ctx.accounts
+            .referrer_pc_account
+            .as_ref()
+            .map(|referrer_pc_account| referrer_pc_account.key),
+        amount,
+    )?;
+    solana_program::program::invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Creates and invokes a [library::native_instrcutions::withdraw_with_min] instruction.
+///
+/// # Arguments
+///
+/// See [library::native_instrcutions::WithdrawInstruction].
+///
+/// * `amount` - Pool token amount to transfer.
+/// * `min_coin_amount` - Minimum coin amount to withdraw, prevents excessive slippage.
+/// * `min_pc_amount` - Minimum pc amount to withdraw, prevents excessive slippage.
+///
+/// `referrer_pc_account` of the context is passed as the optional trailing account if set.
+pub fn withdraw_with_min<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, Withdraw<'info>>,
+    amount: u64,
+    min_coin_amount: u64,
+    min_pc_amount: u64,
+) -> Result<()> {
+    let ix = native_instrcutions::withdraw_with_min(
+        ctx.program.key,
+        ctx.accounts.amm.key,
+        ctx.accounts.amm_authority.key,
+        ctx.accounts.amm_open_orders.key,
+        ctx.accounts.amm_target_orders.key,
+        ctx.accounts.amm_lp_mint.key,
+        ctx.accounts.amm_coin_vault.key,
+        ctx.accounts.amm_pc_vault.key,
+        ctx.accounts.market_program.key,
+        ctx.accounts.market.key,
+        ctx.accounts.market_coin_vault.key,
+        ctx.accounts.market_pc_vault.key,
+        ctx.accounts.market_vault_signer.key,
+        ctx.accounts.user_token_lp.key,
+        ctx.accounts.user_token_coin.key,
+        ctx.accounts.user_token_pc.key,
+        ctx.accounts.user_owner.key,
+        ctx.accounts.market_event_q.key,
+        ctx.accounts.market_bids.key,
+        ctx.accounts.market_asks.key,
+        ctx.accounts
+            .referrer_pc_account
+            .as_ref()
+            .map(|referrer_pc_account| referrer_pc_account.key),
         amount,
+        min_coin_amount,
+        min_pc_amount,
     )?;
     solana_program::program::invoke_signed(
         &ix,
@@ -223,3 +329,71 @@ pub fn swap_base_out<'a, 'b, 'c, 'info>(
     )?;
     Ok(())
 }
+
+/// Creates and invokes a [library::native_instrcutions::swap_base_in_v2] instruction,
+/// which doesn't need the OpenBook market accounts.
+///
+/// # Arguments
+///
+/// See [library::native_instrcutions::SwapInstructionBaseIn].
+///
+/// * `amount_in` - SOURCE amount to transfer, output to DESTINATION is based on the exchange rate.
+/// * `minimum_amount_out` - Minimum amount of DESTINATION token to output, prevents excessive slippage.
+pub fn swap_base_in_v2<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, SwapBaseInV2<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    let ix = native_instrcutions::swap_base_in_v2(
+        ctx.program.key,
+        ctx.accounts.amm.key,
+        ctx.accounts.amm_authority.key,
+        ctx.accounts.amm_coin_vault.key,
+        ctx.accounts.amm_pc_vault.key,
+        ctx.accounts.user_token_source.key,
+        ctx.accounts.user_token_destination.key,
+        ctx.accounts.user_source_owner.key,
+        amount_in,
+        minimum_amount_out,
+    )?;
+    solana_program::program::invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Creates and invokes a [library::native_instrcutions::swap_base_out_v2] instruction,
+/// which doesn't need the OpenBook market accounts.
+///
+/// # Arguments
+///
+/// See [library::native_instrcutions::SwapInstructionBaseOut].
+///
+/// * `max_amount_in` - Maximum amount of SOURCE token to transfer, prevents excessive slippage.
+/// * `amount_out` - Amount of DESTINATION token to output.
+pub fn swap_base_out_v2<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, SwapBaseOutV2<'info>>,
+    max_amount_in: u64,
+    amount_out: u64,
+) -> Result<()> {
+    let ix = native_instrcutions::swap_base_out_v2(
+        ctx.program.key,
+        ctx.accounts.amm.key,
+        ctx.accounts.amm_authority.key,
+        ctx.accounts.amm_coin_vault.key,
+        ctx.accounts.amm_pc_vault.key,
+        ctx.accounts.user_token_source.key,
+        ctx.accounts.user_token_destination.key,
+        ctx.accounts.user_source_owner.key,
+        max_amount_in,
+        amount_out,
+    )?;
+    solana_program::program::invoke_signed(
+        &ix,
+        &ToAccountInfos::to_account_infos(&ctx),
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
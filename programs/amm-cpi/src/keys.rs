@@ -0,0 +1,593 @@
+//! Account keys of an amm pool and its market, to fill the instruction accounts.
+use crate::{
+    library::native_instrcutions, openbook::MarketState, pda::AUTHORITY_AMM, states::AmmInfo, *,
+};
+use anchor_lang::{error::ErrorCode, solana_program::instruction::Instruction};
+
+/// All the pool and market accounts used by `SwapBaseIn`, `SwapBaseOut` and `Withdraw`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AmmKeys {
+    /// The amm account
+    pub amm: Pubkey,
+    /// The amm authority
+    pub amm_authority: Pubkey,
+    /// The amm open orders account
+    pub amm_open_orders: Pubkey,
+    /// The amm target orders account
+    pub amm_target_orders: Pubkey,
+    /// The pool lp mint
+    pub amm_lp_mint: Pubkey,
+    /// The amm coin vault
+    pub amm_coin_vault: Pubkey,
+    /// The amm pc vault
+    pub amm_pc_vault: Pubkey,
+    /// The OpenBook program
+    pub market_program: Pubkey,
+    /// The OpenBook market
+    pub market: Pubkey,
+    /// The market bids
+    pub market_bids: Pubkey,
+    /// The market asks
+    pub market_asks: Pubkey,
+    /// The market event queue
+    pub market_event_queue: Pubkey,
+    /// The market coin vault
+    pub market_coin_vault: Pubkey,
+    /// The market pc vault
+    pub market_pc_vault: Pubkey,
+    /// The market vault signer
+    pub market_vault_signer: Pubkey,
+}
+
+impl AmmKeys {
+    /// Read the keys from the amm account and its market account
+    pub fn load(amm: &AccountInfo, market: &AccountInfo) -> Result<Self> {
+        let amm_info = AmmInfo::load_checked(amm)?;
+        let market_key = amm_info.market;
+        let market_program = amm_info.market_program;
+        require_keys_eq!(market.key(), market_key, ErrorCode::ConstraintAddress);
+        let market_state = MarketState::load_checked(market, &market_program)?;
+        Self::from_states(amm.key(), &amm_info, &market_state)
+    }
+
+    /// Build the keys from the decoded amm and market accounts
+    pub fn from_states(
+        amm: Pubkey,
+        amm_info: &AmmInfo,
+        market_state: &MarketState,
+    ) -> Result<Self> {
+        let amm_authority =
+            Pubkey::create_program_address(&[AUTHORITY_AMM, &[amm_info.nonce as u8]], &crate::id())
+                .map_err(|_| error!(ErrorCode::ConstraintSeeds))?;
+        Ok(Self {
+            amm,
+            amm_authority,
+            amm_open_orders: amm_info.open_orders,
+            amm_target_orders: amm_info.target_orders,
+            amm_lp_mint: amm_info.lp_mint,
+            amm_coin_vault: amm_info.coin_vault,
+            amm_pc_vault: amm_info.pc_vault,
+            market_program: amm_info.market_program,
+            market: amm_info.market,
+            market_bids: market_state.bids,
+            market_asks: market_state.asks,
+            market_event_queue: market_state.event_q,
+            market_coin_vault: market_state.coin_vault,
+            market_pc_vault: market_state.pc_vault,
+            market_vault_signer: market_state.vault_signer(&amm_info.market_program)?,
+        })
+    }
+
+    /// Check the pool accounts against a single decode of the amm account, fails with
+    /// `ConstraintAddress` naming the first mismatched account. `amm_target_orders` and
+    /// `amm_lp_mint` are skipped when `None`, as the swap instructions don't use them.
+    pub fn validate(
+        amm: &AccountInfo,
+        amm_open_orders: &Pubkey,
+        amm_target_orders: Option<&Pubkey>,
+        amm_lp_mint: Option<&Pubkey>,
+        amm_coin_vault: &Pubkey,
+        amm_pc_vault: &Pubkey,
+        market: &Pubkey,
+    ) -> Result<()> {
+        let amm_info = AmmInfo::load_checked(amm)?;
+        check_key("amm_open_orders", amm_open_orders, &amm_info.open_orders)?;
+        if let Some(amm_target_orders) = amm_target_orders {
+            check_key(
+                "amm_target_orders",
+                amm_target_orders,
+                &amm_info.target_orders,
+            )?;
+        }
+        if let Some(amm_lp_mint) = amm_lp_mint {
+            check_key("amm_lp_mint", amm_lp_mint, &amm_info.lp_mint)?;
+        }
+        check_key("amm_coin_vault", amm_coin_vault, &amm_info.coin_vault)?;
+        check_key("amm_pc_vault", amm_pc_vault, &amm_info.pc_vault)?;
+        check_key("market", market, &amm_info.market)
+    }
+
+    /// Build the `SwapBaseIn` instruction of the pool
+    pub fn swap_base_in_instruction(
+        &self,
+        user_token_source: &Pubkey,
+        user_token_destination: &Pubkey,
+        user_source_owner: &Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<Instruction> {
+        Ok(native_instrcutions::swap_base_in(
+            &crate::id(),
+            &self.amm,
+            &self.amm_authority,
+            &self.amm_open_orders,
+            &self.amm_coin_vault,
+            &self.amm_pc_vault,
+            &self.market_program,
+            &self.market,
+            &self.market_bids,
+            &self.market_asks,
+            &self.market_event_queue,
+            &self.market_coin_vault,
+            &self.market_pc_vault,
+            &self.market_vault_signer,
+            user_token_source,
+            user_token_destination,
+            user_source_owner,
+            amount_in,
+            minimum_amount_out,
+        )?)
+    }
+
+    /// Build the account infos of [AmmKeys::swap_base_in_instruction] in the instruction order,
+    /// the pool and market accounts are picked from `accounts`, which may hold them in any order
+    pub fn swap_base_in_accounts<'info>(
+        &self,
+        accounts: &[AccountInfo<'info>],
+        user_token_source: AccountInfo<'info>,
+        user_token_destination: AccountInfo<'info>,
+        user_source_owner: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+    ) -> Result<Vec<AccountInfo<'info>>> {
+        Ok(vec![
+            token_program,
+            find_account(accounts, &self.amm)?,
+            find_account(accounts, &self.amm_authority)?,
+            find_account(accounts, &self.amm_open_orders)?,
+            find_account(accounts, &self.amm_coin_vault)?,
+            find_account(accounts, &self.amm_pc_vault)?,
+            find_account(accounts, &self.market_program)?,
+            find_account(accounts, &self.market)?,
+            find_account(accounts, &self.market_bids)?,
+            find_account(accounts, &self.market_asks)?,
+            find_account(accounts, &self.market_event_queue)?,
+            find_account(accounts, &self.market_coin_vault)?,
+            find_account(accounts, &self.market_pc_vault)?,
+            find_account(accounts, &self.market_vault_signer)?,
+            user_token_source,
+            user_token_destination,
+            user_source_owner,
+        ])
+    }
+
+    /// Build the `Withdraw` instruction of the pool
+    pub fn withdraw_instruction(
+        &self,
+        user_token_lp: &Pubkey,
+        user_token_coin: &Pubkey,
+        user_token_pc: &Pubkey,
+        user_owner: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction> {
+        Ok(native_instrcutions::withdraw(
+            &crate::id(),
+            &self.amm,
+            &self.amm_authority,
+            &self.amm_open_orders,
+            &self.amm_target_orders,
+            &self.amm_lp_mint,
+            &self.amm_coin_vault,
+            &self.amm_pc_vault,
+            &self.market_program,
+            &self.market,
+            &self.market_coin_vault,
+            &self.market_pc_vault,
+            &self.market_vault_signer,
+            user_token_lp,
+            user_token_coin,
+            user_token_pc,
+            user_owner,
+            &self.market_event_queue,
+            &self.market_bids,
+            &self.market_asks,
+            None,
+            amount,
+        )?)
+    }
+
+    /// Build the account infos of [AmmKeys::withdraw_instruction] in the instruction order,
+    /// the pool and market accounts are picked from `accounts`, which may hold them in any order
+    pub fn withdraw_accounts<'info>(
+        &self,
+        accounts: &[AccountInfo<'info>],
+        user_token_lp: AccountInfo<'info>,
+        user_token_coin: AccountInfo<'info>,
+        user_token_pc: AccountInfo<'info>,
+        user_owner: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+    ) -> Result<Vec<AccountInfo<'info>>> {
+        Ok(vec![
+            token_program,
+            find_account(accounts, &self.amm)?,
+            find_account(accounts, &self.amm_authority)?,
+            find_account(accounts, &self.amm_open_orders)?,
+            find_account(accounts, &self.amm_target_orders)?,
+            find_account(accounts, &self.amm_lp_mint)?,
+            find_account(accounts, &self.amm_coin_vault)?,
+            find_account(accounts, &self.amm_pc_vault)?,
+            find_account(accounts, &self.market_program)?,
+            find_account(accounts, &self.market)?,
+            find_account(accounts, &self.market_coin_vault)?,
+            find_account(accounts, &self.market_pc_vault)?,
+            find_account(accounts, &self.market_vault_signer)?,
+            user_token_lp,
+            user_token_coin,
+            user_token_pc,
+            user_owner,
+            find_account(accounts, &self.market_event_queue)?,
+            find_account(accounts, &self.market_bids)?,
+            find_account(accounts, &self.market_asks)?,
+        ])
+    }
+}
+
+fn check_key(name: &str, key: &Pubkey, expected: &Pubkey) -> Result<()> {
+    if key != expected {
+        return Err(error!(ErrorCode::ConstraintAddress)
+            .with_account_name(name)
+            .with_pubkeys((*key, *expected)));
+    }
+    Ok(())
+}
+
+fn find_account<'info>(
+    accounts: &[AccountInfo<'info>],
+    key: &Pubkey,
+) -> Result<AccountInfo<'info>> {
+    accounts
+        .iter()
+        .find(|account| account.key == key)
+        .cloned()
+        .ok_or_else(|| {
+            msg!("Missing account {}", key);
+            error!(ErrorCode::AccountNotEnoughKeys)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openbook::{ACCOUNT_HEAD_PADDING, ACCOUNT_TAIL_PADDING};
+    use anchor_lang::{
+        __private::bytemuck,
+        error::{ComparedValues, ErrorOrigin},
+    };
+
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn new(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> Self {
+            Self {
+                key,
+                owner,
+                lamports: 0,
+                data,
+            }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                false,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    fn states() -> (AmmInfo, MarketState) {
+        let market = Pubkey::new_unique();
+        let market_program = crate::openbook_program_id::id();
+        let mut amm_info: AmmInfo = bytemuck::Zeroable::zeroed();
+        amm_info.nonce = crate::pda::get_amm_authority_nonce() as u64;
+        amm_info.open_orders = Pubkey::new_unique();
+        amm_info.target_orders = Pubkey::new_unique();
+        amm_info.lp_mint = Pubkey::new_unique();
+        amm_info.coin_vault = Pubkey::new_unique();
+        amm_info.pc_vault = Pubkey::new_unique();
+        amm_info.market = market;
+        amm_info.market_program = market_program;
+
+        let mut market_state: MarketState = bytemuck::Zeroable::zeroed();
+        market_state.own_address = market;
+        market_state.vault_signer_nonce = (0..)
+            .find(|nonce: &u64| {
+                Pubkey::create_program_address(
+                    &[market.as_ref(), &nonce.to_le_bytes()],
+                    &market_program,
+                )
+                .is_ok()
+            })
+            .unwrap();
+        market_state.bids = Pubkey::new_unique();
+        market_state.asks = Pubkey::new_unique();
+        market_state.event_q = Pubkey::new_unique();
+        market_state.coin_vault = Pubkey::new_unique();
+        market_state.pc_vault = Pubkey::new_unique();
+        (amm_info, market_state)
+    }
+
+    fn market_data(market_state: &MarketState) -> Vec<u8> {
+        [
+            ACCOUNT_HEAD_PADDING,
+            bytemuck::bytes_of(market_state),
+            ACCOUNT_TAIL_PADDING,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn from_states() {
+        let amm = Pubkey::new_unique();
+        let (amm_info, market_state) = states();
+        let keys = AmmKeys::from_states(amm, &amm_info, &market_state).unwrap();
+        let market_program = crate::openbook_program_id::id();
+        assert_eq!(
+            keys,
+            AmmKeys {
+                amm,
+                amm_authority: pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+                amm_open_orders: amm_info.open_orders,
+                amm_target_orders: amm_info.target_orders,
+                amm_lp_mint: amm_info.lp_mint,
+                amm_coin_vault: amm_info.coin_vault,
+                amm_pc_vault: amm_info.pc_vault,
+                market_program,
+                market: amm_info.market,
+                market_bids: market_state.bids,
+                market_asks: market_state.asks,
+                market_event_queue: market_state.event_q,
+                market_coin_vault: market_state.coin_vault,
+                market_pc_vault: market_state.pc_vault,
+                market_vault_signer: market_state.vault_signer(&market_program).unwrap(),
+            }
+        );
+
+        let mut amm_info = amm_info;
+        amm_info.nonce = 256;
+        assert_eq!(
+            AmmKeys::from_states(amm, &amm_info, &market_state).unwrap_err(),
+            ErrorCode::ConstraintSeeds.into()
+        );
+    }
+
+    #[test]
+    fn load() {
+        let (amm_info, market_state) = states();
+        let mut amm = TestAccount::new(
+            Pubkey::new_unique(),
+            crate::id(),
+            bytemuck::bytes_of(&amm_info).to_vec(),
+        );
+        let mut market = TestAccount::new(
+            amm_info.market,
+            amm_info.market_program,
+            market_data(&market_state),
+        );
+        let mut other = TestAccount::new(
+            Pubkey::new_unique(),
+            amm_info.market_program,
+            market_data(&market_state),
+        );
+        let keys = AmmKeys::load(&amm.info(), &market.info()).unwrap();
+        assert_eq!(
+            keys,
+            AmmKeys::from_states(amm.key, &amm_info, &market_state).unwrap()
+        );
+        assert_eq!(
+            AmmKeys::load(&amm.info(), &other.info()).unwrap_err(),
+            ErrorCode::ConstraintAddress.into()
+        );
+        assert_eq!(
+            AmmKeys::load(&other.info(), &market.info()).unwrap_err(),
+            ErrorCode::AccountOwnedByWrongProgram.into()
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let (amm_info, _) = states();
+        let mut amm = TestAccount::new(
+            Pubkey::new_unique(),
+            crate::id(),
+            bytemuck::bytes_of(&amm_info).to_vec(),
+        );
+        let amm = amm.info();
+        let validate = |amm_target_orders: Option<&Pubkey>, amm_pc_vault: &Pubkey| {
+            AmmKeys::validate(
+                &amm,
+                &amm_info.open_orders,
+                amm_target_orders,
+                Some(&amm_info.lp_mint),
+                &amm_info.coin_vault,
+                amm_pc_vault,
+                &amm_info.market,
+            )
+        };
+        assert!(validate(Some(&amm_info.target_orders), &amm_info.pc_vault).is_ok());
+        assert!(validate(None, &amm_info.pc_vault).is_ok());
+
+        let other = Pubkey::new_unique();
+        for (error, name, expected) in [
+            (
+                validate(Some(&other), &amm_info.pc_vault).unwrap_err(),
+                "amm_target_orders",
+                amm_info.target_orders,
+            ),
+            (
+                validate(None, &other).unwrap_err(),
+                "amm_pc_vault",
+                amm_info.pc_vault,
+            ),
+        ] {
+            match error {
+                Error::AnchorError(error) => {
+                    assert_eq!(error.error_code_number, ErrorCode::ConstraintAddress as u32);
+                    assert!(matches!(
+                        &error.error_origin,
+                        Some(ErrorOrigin::AccountName(account_name)) if account_name == name
+                    ));
+                    assert!(matches!(
+                        &error.compared_values,
+                        Some(ComparedValues::Pubkeys(pubkeys)) if *pubkeys == (other, expected)
+                    ));
+                }
+                error => panic!("{:?}", error),
+            }
+        }
+    }
+
+    #[test]
+    fn swap_base_in_accounts() {
+        let (amm_info, market_state) = states();
+        let keys = AmmKeys::from_states(Pubkey::new_unique(), &amm_info, &market_state).unwrap();
+        let pool_keys = [
+            keys.amm,
+            keys.amm_authority,
+            keys.amm_open_orders,
+            keys.amm_coin_vault,
+            keys.amm_pc_vault,
+            keys.market_program,
+            keys.market,
+            keys.market_bids,
+            keys.market_asks,
+            keys.market_event_queue,
+            keys.market_coin_vault,
+            keys.market_pc_vault,
+            keys.market_vault_signer,
+        ];
+        let mut pool_accounts: Vec<TestAccount> = pool_keys
+            .iter()
+            .rev()
+            .map(|key| TestAccount::new(*key, Pubkey::default(), vec![]))
+            .collect();
+        let mut user_accounts: Vec<TestAccount> = (0..3)
+            .map(|_| TestAccount::new(Pubkey::new_unique(), Pubkey::default(), vec![]))
+            .collect();
+        let mut token_program = TestAccount::new(anchor_spl::token::ID, Pubkey::default(), vec![]);
+        let user_keys: Vec<Pubkey> = user_accounts.iter().map(|account| account.key).collect();
+
+        let ix = keys
+            .swap_base_in_instruction(&user_keys[0], &user_keys[1], &user_keys[2], 1, 2)
+            .unwrap();
+        let pool_infos: Vec<AccountInfo> =
+            pool_accounts.iter_mut().map(TestAccount::info).collect();
+        let user_infos: Vec<AccountInfo> =
+            user_accounts.iter_mut().map(TestAccount::info).collect();
+        let token_program = token_program.info();
+        let infos = keys
+            .swap_base_in_accounts(
+                &pool_infos,
+                user_infos[0].clone(),
+                user_infos[1].clone(),
+                user_infos[2].clone(),
+                token_program.clone(),
+            )
+            .unwrap();
+        let info_keys: Vec<Pubkey> = infos.iter().map(|info| *info.key).collect();
+        let meta_keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(info_keys, meta_keys);
+
+        assert_eq!(
+            keys.swap_base_in_accounts(
+                &pool_infos[1..],
+                user_infos[0].clone(),
+                user_infos[1].clone(),
+                user_infos[2].clone(),
+                token_program.clone(),
+            )
+            .unwrap_err(),
+            ErrorCode::AccountNotEnoughKeys.into()
+        );
+    }
+
+    #[test]
+    fn withdraw_accounts() {
+        let (amm_info, market_state) = states();
+        let keys = AmmKeys::from_states(Pubkey::new_unique(), &amm_info, &market_state).unwrap();
+        let pool_keys = [
+            keys.amm,
+            keys.amm_authority,
+            keys.amm_open_orders,
+            keys.amm_target_orders,
+            keys.amm_lp_mint,
+            keys.amm_coin_vault,
+            keys.amm_pc_vault,
+            keys.market_program,
+            keys.market,
+            keys.market_bids,
+            keys.market_asks,
+            keys.market_event_queue,
+            keys.market_coin_vault,
+            keys.market_pc_vault,
+            keys.market_vault_signer,
+        ];
+        let mut pool_accounts: Vec<TestAccount> = pool_keys
+            .iter()
+            .rev()
+            .map(|key| TestAccount::new(*key, Pubkey::default(), vec![]))
+            .collect();
+        let mut user_accounts: Vec<TestAccount> = (0..4)
+            .map(|_| TestAccount::new(Pubkey::new_unique(), Pubkey::default(), vec![]))
+            .collect();
+        let mut token_program = TestAccount::new(anchor_spl::token::ID, Pubkey::default(), vec![]);
+        let user_keys: Vec<Pubkey> = user_accounts.iter().map(|account| account.key).collect();
+
+        let ix = keys
+            .withdraw_instruction(
+                &user_keys[0],
+                &user_keys[1],
+                &user_keys[2],
+                &user_keys[3],
+                1,
+            )
+            .unwrap();
+        let pool_infos: Vec<AccountInfo> =
+            pool_accounts.iter_mut().map(TestAccount::info).collect();
+        let user_infos: Vec<AccountInfo> =
+            user_accounts.iter_mut().map(TestAccount::info).collect();
+        let token_program = token_program.info();
+        let infos = keys
+            .withdraw_accounts(
+                &pool_infos,
+                user_infos[0].clone(),
+                user_infos[1].clone(),
+                user_infos[2].clone(),
+                user_infos[3].clone(),
+                token_program.clone(),
+            )
+            .unwrap();
+        let info_keys: Vec<Pubkey> = infos.iter().map(|info| *info.key).collect();
+        let meta_keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(info_keys, meta_keys);
+    }
+}
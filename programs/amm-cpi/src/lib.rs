@@ -6,10 +6,19 @@
 
 pub mod context;
 pub mod instructions;
+pub mod keys;
 pub mod library;
+pub mod log;
+pub mod openbook;
+pub mod pda;
+pub mod quote;
+pub mod states;
 
 pub use context::*;
 pub use instructions::*;
+pub use keys::*;
+pub use pda::*;
+pub use states::*;
 
 use anchor_lang::prelude::*;
 
@@ -2,6 +2,7 @@
 
 #![allow(clippy::too_many_arguments)]
 
+use crate::states::Fees;
 use anchor_lang::{
     prelude::declare_id,
     solana_program::{
@@ -46,6 +47,33 @@ pub struct PreInitializeInstruction {
     pub nonce: u8,
 }
 
+/// The side of a deposit which is fixed, the other side is computed from the pool ratio
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BaseSide {
+    /// Deposit `max_coin_amount` coin, and at most `max_pc_amount` pc
+    Coin = 0,
+    /// Deposit `max_pc_amount` pc, and at most `max_coin_amount` coin
+    Pc = 1,
+}
+
+impl BaseSide {
+    /// Decode the base side, returns `None` on an unknown value
+    pub fn from_u64(base_side: u64) -> Option<Self> {
+        match base_side {
+            0 => Some(BaseSide::Coin),
+            1 => Some(BaseSide::Pc),
+            _ => None,
+        }
+    }
+}
+
+impl From<BaseSide> for u64 {
+    fn from(base_side: BaseSide) -> Self {
+        base_side as u64
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct DepositInstruction {
@@ -53,7 +81,11 @@ pub struct DepositInstruction {
     /// the current exchange rate and size of the pool
     pub max_coin_amount: u64,
     pub max_pc_amount: u64,
+    /// See `BaseSide`
     pub base_side: u64,
+    /// Minimum amount of the other side to deposit, prevents excessive slippage.
+    /// Optional trailing value, older clients don't send it.
+    pub other_amount_min: Option<u64>,
 }
 
 #[repr(C)]
@@ -62,6 +94,10 @@ pub struct WithdrawInstruction {
     /// Pool token amount to transfer. token_a and token_b amount are set by
     /// the current exchange rate and size of the pool
     pub amount: u64,
+    /// Minimum coin and pc amounts to withdraw, prevents excessive slippage.
+    /// Optional trailing values, either both or none of them are set.
+    pub min_coin_amount: Option<u64>,
+    pub min_pc_amount: Option<u64>,
 }
 
 #[repr(C)]
@@ -82,6 +118,140 @@ pub struct SwapInstructionBaseOut {
     pub amount_out: u64,
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MonitorStepInstruction {
+    /// max value of plan/new/cancel orders
+    pub plan_order_limit: u16,
+    pub place_order_limit: u16,
+    pub cancel_order_limit: u16,
+}
+
+/// Params of the `SetParams` instruction
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmParams {
+    Status = 0,
+    State = 1,
+    OrderNum = 2,
+    Depth = 3,
+    AmountWave = 4,
+    MinPriceMultiplier = 5,
+    MaxPriceMultiplier = 6,
+    MinSize = 7,
+    VolMaxCutRatio = 8,
+    Fees = 9,
+    AmmOwner = 10,
+    SetOpenTime = 11,
+    LastOrderDistance = 12,
+    InitOrderDepth = 13,
+    SetSwitchTime = 14,
+    ClearOpenTime = 15,
+    Seperate = 16,
+    UpdateOpenOrder = 17,
+}
+
+impl AmmParams {
+    /// Decode the param, returns `None` on an unknown value
+    pub fn from_u64(param: u64) -> Option<Self> {
+        Some(match param {
+            0 => AmmParams::Status,
+            1 => AmmParams::State,
+            2 => AmmParams::OrderNum,
+            3 => AmmParams::Depth,
+            4 => AmmParams::AmountWave,
+            5 => AmmParams::MinPriceMultiplier,
+            6 => AmmParams::MaxPriceMultiplier,
+            7 => AmmParams::MinSize,
+            8 => AmmParams::VolMaxCutRatio,
+            9 => AmmParams::Fees,
+            10 => AmmParams::AmmOwner,
+            11 => AmmParams::SetOpenTime,
+            12 => AmmParams::LastOrderDistance,
+            13 => AmmParams::InitOrderDepth,
+            14 => AmmParams::SetSwitchTime,
+            15 => AmmParams::ClearOpenTime,
+            16 => AmmParams::Seperate,
+            17 => AmmParams::UpdateOpenOrder,
+            _ => return None,
+        })
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LastOrderDistance {
+    pub last_order_numerator: u64,
+    pub last_order_denominator: u64,
+}
+
+/// Exactly one of the optional values is set, depending on `param`:
+/// `new_pubkey` for `AmmParams::AmmOwner`, `fees` for `AmmParams::Fees`,
+/// `last_order_distance` for `AmmParams::LastOrderDistance` and `value` for the others.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SetParamsInstruction {
+    pub param: u8,
+    pub value: Option<u64>,
+    pub new_pubkey: Option<Pubkey>,
+    pub fees: Option<Fees>,
+    pub last_order_distance: Option<LastOrderDistance>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WithdrawSrmInstruction {
+    pub amount: u64,
+}
+
+/// Params of the `SimulateInfo` instruction
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimulateParams {
+    PoolInfo = 0,
+    SwapBaseInInfo = 1,
+    SwapBaseOutInfo = 2,
+    RunCrankInfo = 3,
+}
+
+impl SimulateParams {
+    /// Decode the param, returns `None` on an unknown value
+    pub fn from_u64(param: u64) -> Option<Self> {
+        Some(match param {
+            0 => SimulateParams::PoolInfo,
+            1 => SimulateParams::SwapBaseInInfo,
+            2 => SimulateParams::SwapBaseOutInfo,
+            3 => SimulateParams::RunCrankInfo,
+            _ => return None,
+        })
+    }
+}
+
+/// `swap_base_in_value` is set for `SimulateParams::SwapBaseInInfo`,
+/// `swap_base_out_value` for `SimulateParams::SwapBaseOutInfo`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SimulateInstruction {
+    pub param: u8,
+    pub swap_base_in_value: Option<SwapInstructionBaseIn>,
+    pub swap_base_out_value: Option<SwapInstructionBaseOut>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AdminCancelOrdersInstruction {
+    pub limit: u16,
+}
+
+/// `owner` is set for param 0 and 1, `create_pool_fee` for param 2.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ConfigArgs {
+    pub param: u8,
+    pub owner: Option<Pubkey>,
+    pub create_pool_fee: Option<u64>,
+}
+
 /// Instructions supported by the AmmInfo program.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq)]
@@ -117,7 +287,8 @@ pub enum AmmInstruction {
     ///   20. `[writable]` User destination lp token ATA Account
     Initialize2(InitializeInstruction2),
 
-    Reserved0,
+    ///   MonitorStep. To monitor place Amm order state machine turn around step by step.
+    MonitorStep(MonitorStepInstruction),
 
     ///   Deposit some tokens into the pool.  The output is a "pool" token representing ownership
     ///   into the pool. Inputs are converted to the current ratio.
@@ -162,13 +333,17 @@ pub enum AmmInstruction {
     ///   19. `[writable]` Market asks Account
     Withdraw(WithdrawInstruction),
 
-    Reserved1,
+    ///   Migrate the associated market from Serum to OpenBook.
+    MigrateToOpenBook,
 
-    Reserved2,
+    ///   Set the params of an AMM pool, admin only.
+    SetParams(SetParamsInstruction),
 
-    Reserved3,
+    ///   Withdraw the pnl of the pool to the pnl owner, admin only.
+    WithdrawPnl,
 
-    Reserved4,
+    ///   Withdraw the srm token of the pool, admin only.
+    WithdrawSrm(WithdrawSrmInstruction),
 
     /// Swap coin or pc from pool, base amount_in with a slippage of minimum_amount_out
     ///
@@ -219,21 +394,81 @@ pub enum AmmInstruction {
     ///   17. `[singer]` User wallet Account
     SwapBaseOut(SwapInstructionBaseOut),
 
-    Reserved5,
+    ///   Simulate the pool info or a swap, the result is written to the program logs.
+    SimulateInfo(SimulateInstruction),
+
+    ///   Cancel the orders of the pool on the market, admin only.
+    AdminCancelOrders(AdminCancelOrdersInstruction),
+
+    ///   Create the config account of the AMM program, admin only.
+    CreateConfigAccount,
+
+    ///   Update the config account of the AMM program, admin only.
+    UpdateConfigAccount(ConfigArgs),
+
+    /// Swap coin or pc from pool, base amount_in with a slippage of minimum_amount_out,
+    /// without the OpenBook market accounts
+    ///
+    ///   0. `[]` Spl Token program id
+    ///   1. `[writable]` AMM Account
+    ///   2. `[]` $authority derived from `create_program_address(&[AUTHORITY_AMM, &[nonce]])`.
+    ///   3. `[writable]` AMM coin vault Account to swap FROM or To.
+    ///   4. `[writable]` AMM pc vault Account to swap FROM or To.
+    ///   5. `[writable]` User source token Account.
+    ///   6. `[writable]` User destination token Account.
+    ///   7. `[signer]` User wallet Account
+    SwapBaseInV2(SwapInstructionBaseIn),
+
+    /// Swap coin or pc from pool, base amount_out with a slippage of max_amount_in,
+    /// without the OpenBook market accounts
+    ///
+    ///   0. `[]` Spl Token program id
+    ///   1. `[writable]` AMM Account
+    ///   2. `[]` $authority derived from `create_program_address(&[AUTHORITY_AMM, &[nonce]])`.
+    ///   3. `[writable]` AMM coin vault Account to swap FROM or To.
+    ///   4. `[writable]` AMM pc vault Account to swap FROM or To.
+    ///   5. `[writable]` User source token Account.
+    ///   6. `[writable]` User destination token Account.
+    ///   7. `[signer]` User wallet Account
+    SwapBaseOutV2(SwapInstructionBaseOut),
 }
 
 impl AmmInstruction {
     /// Unpacks a byte buffer into a [AmmInstruction](enum.AmmInstruction.html).
+    ///
+    /// Fails with `InvalidInstructionData` on an unknown tag or a short buffer. Trailing bytes
+    /// are ignored, as the amm program does.
     pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack_with_rest(input).map(|(instruction, _rest)| instruction)
+    }
+
+    /// Unpacks a byte buffer like [AmmInstruction::unpack], but also fails with
+    /// `InvalidInstructionData` on trailing bytes.
+    pub fn unpack_strict(input: &[u8]) -> Result<Self, ProgramError> {
+        let (instruction, rest) = Self::unpack_with_rest(input)?;
+        Self::unpack_end(rest)?;
+        Ok(instruction)
+    }
+
+    #[allow(deprecated)]
+    fn unpack_with_rest(input: &[u8]) -> Result<(Self, &[u8]), ProgramError> {
         let (&tag, rest) = input
             .split_first()
             .ok_or(ProgramError::InvalidInstructionData)?;
-        Ok(match tag {
+        let unread;
+        let instruction = match tag {
+            0 => {
+                let (nonce, rest) = Self::unpack_u8(rest)?;
+                let (open_time, rest) = Self::unpack_u64(rest)?;
+                unread = rest;
+                Self::Initialize(InitializeInstruction { nonce, open_time })
+            }
             1 => {
                 let (nonce, rest) = Self::unpack_u8(rest)?;
                 let (open_time, rest) = Self::unpack_u64(rest)?;
                 let (init_pc_amount, rest) = Self::unpack_u64(rest)?;
-                let (init_coin_amount, _reset) = Self::unpack_u64(rest)?;
+                let (init_coin_amount, rest) = Self::unpack_u64(rest)?;
+                unread = rest;
                 Self::Initialize2(InitializeInstruction2 {
                     nonce,
                     open_time,
@@ -241,41 +476,214 @@ impl AmmInstruction {
                     init_coin_amount,
                 })
             }
-
+            2 => {
+                let (plan_order_limit, rest) = Self::unpack_u16(rest)?;
+                let (place_order_limit, rest) = Self::unpack_u16(rest)?;
+                let (cancel_order_limit, rest) = Self::unpack_u16(rest)?;
+                unread = rest;
+                Self::MonitorStep(MonitorStepInstruction {
+                    plan_order_limit,
+                    place_order_limit,
+                    cancel_order_limit,
+                })
+            }
             3 => {
                 let (max_coin_amount, rest) = Self::unpack_u64(rest)?;
                 let (max_pc_amount, rest) = Self::unpack_u64(rest)?;
-                let (base_side, _rest) = Self::unpack_u64(rest)?;
+                let (base_side, rest) = Self::unpack_u64(rest)?;
+                let (other_amount_min, rest) = if rest.len() >= 8 {
+                    let (other_amount_min, rest) = Self::unpack_u64(rest)?;
+                    (Some(other_amount_min), rest)
+                } else {
+                    (None, rest)
+                };
+                unread = rest;
                 Self::Deposit(DepositInstruction {
                     max_coin_amount,
                     max_pc_amount,
                     base_side,
+                    other_amount_min,
                 })
             }
             4 => {
-                let (amount, _rest) = Self::unpack_u64(rest)?;
-                Self::Withdraw(WithdrawInstruction { amount })
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                let (min_coin_amount, min_pc_amount, rest) = if rest.len() >= 16 {
+                    let (min_coin_amount, rest) = Self::unpack_u64(rest)?;
+                    let (min_pc_amount, rest) = Self::unpack_u64(rest)?;
+                    (Some(min_coin_amount), Some(min_pc_amount), rest)
+                } else {
+                    (None, None, rest)
+                };
+                unread = rest;
+                Self::Withdraw(WithdrawInstruction {
+                    amount,
+                    min_coin_amount,
+                    min_pc_amount,
+                })
+            }
+            5 => {
+                unread = rest;
+                Self::MigrateToOpenBook
+            }
+            6 => {
+                let (param, rest) = Self::unpack_u8(rest)?;
+                let mut set_params = SetParamsInstruction {
+                    param,
+                    ..Default::default()
+                };
+                match AmmParams::from_u64(param as u64) {
+                    Some(AmmParams::AmmOwner) => {
+                        let (new_pubkey, rest) = Self::unpack_pubkey(rest)?;
+                        unread = rest;
+                        set_params.new_pubkey = Some(new_pubkey);
+                    }
+                    Some(AmmParams::Fees) => {
+                        let (fees, rest) = Self::unpack_fees(rest)?;
+                        unread = rest;
+                        set_params.fees = Some(fees);
+                    }
+                    Some(AmmParams::LastOrderDistance) => {
+                        let (last_order_numerator, rest) = Self::unpack_u64(rest)?;
+                        let (last_order_denominator, rest) = Self::unpack_u64(rest)?;
+                        unread = rest;
+                        set_params.last_order_distance = Some(LastOrderDistance {
+                            last_order_numerator,
+                            last_order_denominator,
+                        });
+                    }
+                    Some(_) => {
+                        let (value, rest) = Self::unpack_u64(rest)?;
+                        unread = rest;
+                        set_params.value = Some(value);
+                    }
+                    None => return Err(ProgramError::InvalidInstructionData),
+                }
+                Self::SetParams(set_params)
+            }
+            7 => {
+                unread = rest;
+                Self::WithdrawPnl
+            }
+            8 => {
+                let (amount, rest) = Self::unpack_u64(rest)?;
+                unread = rest;
+                Self::WithdrawSrm(WithdrawSrmInstruction { amount })
             }
-
             9 => {
                 let (amount_in, rest) = Self::unpack_u64(rest)?;
-                let (minimum_amount_out, _rest) = Self::unpack_u64(rest)?;
+                let (minimum_amount_out, rest) = Self::unpack_u64(rest)?;
+                unread = rest;
                 Self::SwapBaseIn(SwapInstructionBaseIn {
                     amount_in,
                     minimum_amount_out,
                 })
             }
+            10 => {
+                let (nonce, rest) = Self::unpack_u8(rest)?;
+                unread = rest;
+                Self::PreInitialize(PreInitializeInstruction { nonce })
+            }
             11 => {
                 let (max_amount_in, rest) = Self::unpack_u64(rest)?;
-                let (amount_out, _rest) = Self::unpack_u64(rest)?;
+                let (amount_out, rest) = Self::unpack_u64(rest)?;
+                unread = rest;
                 Self::SwapBaseOut(SwapInstructionBaseOut {
                     max_amount_in,
                     amount_out,
                 })
             }
+            12 => {
+                let (param, rest) = Self::unpack_u8(rest)?;
+                let mut simulate = SimulateInstruction {
+                    param,
+                    ..Default::default()
+                };
+                match SimulateParams::from_u64(param as u64) {
+                    Some(SimulateParams::PoolInfo) | Some(SimulateParams::RunCrankInfo) => {
+                        unread = rest;
+                    }
+                    Some(SimulateParams::SwapBaseInInfo) => {
+                        let (amount_in, rest) = Self::unpack_u64(rest)?;
+                        let (minimum_amount_out, rest) = Self::unpack_u64(rest)?;
+                        unread = rest;
+                        simulate.swap_base_in_value = Some(SwapInstructionBaseIn {
+                            amount_in,
+                            minimum_amount_out,
+                        });
+                    }
+                    Some(SimulateParams::SwapBaseOutInfo) => {
+                        let (max_amount_in, rest) = Self::unpack_u64(rest)?;
+                        let (amount_out, rest) = Self::unpack_u64(rest)?;
+                        unread = rest;
+                        simulate.swap_base_out_value = Some(SwapInstructionBaseOut {
+                            max_amount_in,
+                            amount_out,
+                        });
+                    }
+                    None => return Err(ProgramError::InvalidInstructionData),
+                }
+                Self::SimulateInfo(simulate)
+            }
+            13 => {
+                let (limit, rest) = Self::unpack_u16(rest)?;
+                unread = rest;
+                Self::AdminCancelOrders(AdminCancelOrdersInstruction { limit })
+            }
+            14 => {
+                unread = rest;
+                Self::CreateConfigAccount
+            }
+            15 => {
+                let (param, rest) = Self::unpack_u8(rest)?;
+                let mut config_args = ConfigArgs {
+                    param,
+                    ..Default::default()
+                };
+                match param {
+                    0 | 1 => {
+                        let (owner, rest) = Self::unpack_pubkey(rest)?;
+                        unread = rest;
+                        config_args.owner = Some(owner);
+                    }
+                    2 => {
+                        let (create_pool_fee, rest) = Self::unpack_u64(rest)?;
+                        unread = rest;
+                        config_args.create_pool_fee = Some(create_pool_fee);
+                    }
+                    _ => return Err(ProgramError::InvalidInstructionData),
+                }
+                Self::UpdateConfigAccount(config_args)
+            }
+            16 => {
+                let (amount_in, rest) = Self::unpack_u64(rest)?;
+                let (minimum_amount_out, rest) = Self::unpack_u64(rest)?;
+                unread = rest;
+                Self::SwapBaseInV2(SwapInstructionBaseIn {
+                    amount_in,
+                    minimum_amount_out,
+                })
+            }
+            17 => {
+                let (max_amount_in, rest) = Self::unpack_u64(rest)?;
+                let (amount_out, rest) = Self::unpack_u64(rest)?;
+                unread = rest;
+                Self::SwapBaseOutV2(SwapInstructionBaseOut {
+                    max_amount_in,
+                    amount_out,
+                })
+            }
 
             _ => return Err(ProgramError::InvalidInstructionData.into()),
-        })
+        };
+        Ok((instruction, unread))
+    }
+
+    fn unpack_end(input: &[u8]) -> Result<(), ProgramError> {
+        if input.is_empty() {
+            Ok(())
+        } else {
+            Err(ProgramError::InvalidInstructionData)
+        }
     }
 
     fn unpack_u8(input: &[u8]) -> Result<(u8, &[u8]), ProgramError> {
@@ -292,6 +700,73 @@ impl AmmInstruction {
         }
     }
 
+    fn unpack_u16(input: &[u8]) -> Result<(u16, &[u8]), ProgramError> {
+        if input.len() >= 2 {
+            let (amount, rest) = input.split_at(2);
+            let amount = amount
+                .get(..2)
+                .and_then(|slice| slice.try_into().ok())
+                .map(u16::from_le_bytes)
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            Ok((amount, rest))
+        } else {
+            Err(ProgramError::InvalidInstructionData)
+        }
+    }
+
+    fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+        if input.len() >= 32 {
+            let (key, rest) = input.split_at(32);
+            let key = key
+                .try_into()
+                .map(Pubkey::new_from_array)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            Ok((key, rest))
+        } else {
+            Err(ProgramError::InvalidInstructionData)
+        }
+    }
+
+    fn unpack_fees(input: &[u8]) -> Result<(Fees, &[u8]), ProgramError> {
+        let (min_separate_numerator, rest) = Self::unpack_u64(input)?;
+        let (min_separate_denominator, rest) = Self::unpack_u64(rest)?;
+        let (trade_fee_numerator, rest) = Self::unpack_u64(rest)?;
+        let (trade_fee_denominator, rest) = Self::unpack_u64(rest)?;
+        let (pnl_numerator, rest) = Self::unpack_u64(rest)?;
+        let (pnl_denominator, rest) = Self::unpack_u64(rest)?;
+        let (swap_fee_numerator, rest) = Self::unpack_u64(rest)?;
+        let (swap_fee_denominator, rest) = Self::unpack_u64(rest)?;
+        Ok((
+            Fees {
+                min_separate_numerator,
+                min_separate_denominator,
+                trade_fee_numerator,
+                trade_fee_denominator,
+                pnl_numerator,
+                pnl_denominator,
+                swap_fee_numerator,
+                swap_fee_denominator,
+            },
+            rest,
+        ))
+    }
+
+    fn pack_fees(fees: &Fees, buf: &mut Vec<u8>) {
+        let fees = *fees;
+        for value in [
+            fees.min_separate_numerator,
+            fees.min_separate_denominator,
+            fees.trade_fee_numerator,
+            fees.trade_fee_denominator,
+            fees.pnl_numerator,
+            fees.pnl_denominator,
+            fees.swap_fee_numerator,
+            fees.swap_fee_denominator,
+        ] {
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
     fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
         if input.len() >= 8 {
             let (amount, rest) = input.split_at(8);
@@ -307,9 +782,19 @@ impl AmmInstruction {
     }
 
     /// Packs a [AmmInstruction](enum.AmmInstruction.html) into a byte buffer.
+    ///
+    /// Fails with `InvalidInstructionData` if the optional values of `SetParams`,
+    /// `SimulateInfo` or `UpdateConfigAccount` don't match their param, or if only
+    /// one of the minimum amounts of `Withdraw` is set.
+    #[allow(deprecated)]
     pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
         let mut buf = Vec::with_capacity(size_of::<Self>());
         match &*self {
+            Self::Initialize(InitializeInstruction { nonce, open_time }) => {
+                buf.push(0);
+                buf.push(*nonce);
+                buf.extend_from_slice(&open_time.to_le_bytes());
+            }
             Self::Initialize2(InitializeInstruction2 {
                 nonce,
                 open_time,
@@ -322,21 +807,91 @@ impl AmmInstruction {
                 buf.extend_from_slice(&init_pc_amount.to_le_bytes());
                 buf.extend_from_slice(&init_coin_amount.to_le_bytes());
             }
+            Self::MonitorStep(MonitorStepInstruction {
+                plan_order_limit,
+                place_order_limit,
+                cancel_order_limit,
+            }) => {
+                buf.push(2);
+                buf.extend_from_slice(&plan_order_limit.to_le_bytes());
+                buf.extend_from_slice(&place_order_limit.to_le_bytes());
+                buf.extend_from_slice(&cancel_order_limit.to_le_bytes());
+            }
             Self::Deposit(DepositInstruction {
                 max_coin_amount,
                 max_pc_amount,
                 base_side,
+                other_amount_min,
             }) => {
                 buf.push(3);
                 buf.extend_from_slice(&max_coin_amount.to_le_bytes());
                 buf.extend_from_slice(&max_pc_amount.to_le_bytes());
                 buf.extend_from_slice(&base_side.to_le_bytes());
+                if let Some(other_amount_min) = other_amount_min {
+                    buf.extend_from_slice(&other_amount_min.to_le_bytes());
+                }
             }
-            Self::Withdraw(WithdrawInstruction { amount }) => {
+            Self::Withdraw(WithdrawInstruction {
+                amount,
+                min_coin_amount,
+                min_pc_amount,
+            }) => {
                 buf.push(4);
                 buf.extend_from_slice(&amount.to_le_bytes());
+                match (min_coin_amount, min_pc_amount) {
+                    (Some(min_coin_amount), Some(min_pc_amount)) => {
+                        buf.extend_from_slice(&min_coin_amount.to_le_bytes());
+                        buf.extend_from_slice(&min_pc_amount.to_le_bytes());
+                    }
+                    (None, None) => {}
+                    _ => return Err(ProgramError::InvalidInstructionData),
+                }
+            }
+            Self::MigrateToOpenBook => {
+                buf.push(5);
+            }
+            Self::SetParams(SetParamsInstruction {
+                param,
+                value,
+                new_pubkey,
+                fees,
+                last_order_distance,
+            }) => {
+                buf.push(6);
+                buf.push(*param);
+                match AmmParams::from_u64(*param as u64) {
+                    Some(AmmParams::AmmOwner) => {
+                        let new_pubkey = new_pubkey.ok_or(ProgramError::InvalidInstructionData)?;
+                        buf.extend_from_slice(&new_pubkey.to_bytes());
+                    }
+                    Some(AmmParams::Fees) => {
+                        let fees = fees.ok_or(ProgramError::InvalidInstructionData)?;
+                        Self::pack_fees(&fees, &mut buf);
+                    }
+                    Some(AmmParams::LastOrderDistance) => {
+                        let last_order_distance =
+                            last_order_distance.ok_or(ProgramError::InvalidInstructionData)?;
+                        buf.extend_from_slice(
+                            &last_order_distance.last_order_numerator.to_le_bytes(),
+                        );
+                        buf.extend_from_slice(
+                            &last_order_distance.last_order_denominator.to_le_bytes(),
+                        );
+                    }
+                    Some(_) => {
+                        let value = value.ok_or(ProgramError::InvalidInstructionData)?;
+                        buf.extend_from_slice(&value.to_le_bytes());
+                    }
+                    None => return Err(ProgramError::InvalidInstructionData),
+                }
+            }
+            Self::WithdrawPnl => {
+                buf.push(7);
+            }
+            Self::WithdrawSrm(WithdrawSrmInstruction { amount }) => {
+                buf.push(8);
+                buf.extend_from_slice(&amount.to_le_bytes());
             }
-
             Self::SwapBaseIn(SwapInstructionBaseIn {
                 amount_in,
                 minimum_amount_out,
@@ -345,6 +900,10 @@ impl AmmInstruction {
                 buf.extend_from_slice(&amount_in.to_le_bytes());
                 buf.extend_from_slice(&minimum_amount_out.to_le_bytes());
             }
+            Self::PreInitialize(PreInitializeInstruction { nonce }) => {
+                buf.push(10);
+                buf.push(*nonce);
+            }
             Self::SwapBaseOut(SwapInstructionBaseOut {
                 max_amount_in,
                 amount_out,
@@ -353,7 +912,73 @@ impl AmmInstruction {
                 buf.extend_from_slice(&max_amount_in.to_le_bytes());
                 buf.extend_from_slice(&amount_out.to_le_bytes());
             }
-            _ => {}
+            Self::SimulateInfo(SimulateInstruction {
+                param,
+                swap_base_in_value,
+                swap_base_out_value,
+            }) => {
+                buf.push(12);
+                buf.push(*param);
+                match SimulateParams::from_u64(*param as u64) {
+                    Some(SimulateParams::PoolInfo) | Some(SimulateParams::RunCrankInfo) => {}
+                    Some(SimulateParams::SwapBaseInInfo) => {
+                        let swap_base_in =
+                            swap_base_in_value.ok_or(ProgramError::InvalidInstructionData)?;
+                        buf.extend_from_slice(&swap_base_in.amount_in.to_le_bytes());
+                        buf.extend_from_slice(&swap_base_in.minimum_amount_out.to_le_bytes());
+                    }
+                    Some(SimulateParams::SwapBaseOutInfo) => {
+                        let swap_base_out =
+                            swap_base_out_value.ok_or(ProgramError::InvalidInstructionData)?;
+                        buf.extend_from_slice(&swap_base_out.max_amount_in.to_le_bytes());
+                        buf.extend_from_slice(&swap_base_out.amount_out.to_le_bytes());
+                    }
+                    None => return Err(ProgramError::InvalidInstructionData),
+                }
+            }
+            Self::AdminCancelOrders(AdminCancelOrdersInstruction { limit }) => {
+                buf.push(13);
+                buf.extend_from_slice(&limit.to_le_bytes());
+            }
+            Self::CreateConfigAccount => {
+                buf.push(14);
+            }
+            Self::UpdateConfigAccount(ConfigArgs {
+                param,
+                owner,
+                create_pool_fee,
+            }) => {
+                buf.push(15);
+                buf.push(*param);
+                match param {
+                    0 | 1 => {
+                        let owner = owner.ok_or(ProgramError::InvalidInstructionData)?;
+                        buf.extend_from_slice(&owner.to_bytes());
+                    }
+                    2 => {
+                        let create_pool_fee =
+                            create_pool_fee.ok_or(ProgramError::InvalidInstructionData)?;
+                        buf.extend_from_slice(&create_pool_fee.to_le_bytes());
+                    }
+                    _ => return Err(ProgramError::InvalidInstructionData),
+                }
+            }
+            Self::SwapBaseInV2(SwapInstructionBaseIn {
+                amount_in,
+                minimum_amount_out,
+            }) => {
+                buf.push(16);
+                buf.extend_from_slice(&amount_in.to_le_bytes());
+                buf.extend_from_slice(&minimum_amount_out.to_le_bytes());
+            }
+            Self::SwapBaseOutV2(SwapInstructionBaseOut {
+                max_amount_in,
+                amount_out,
+            }) => {
+                buf.push(17);
+                buf.extend_from_slice(&max_amount_in.to_le_bytes());
+                buf.extend_from_slice(&amount_out.to_le_bytes());
+            }
         }
         Ok(buf)
     }
@@ -451,6 +1076,7 @@ pub fn deposit(
         max_coin_amount,
         max_pc_amount,
         base_side,
+        other_amount_min: None,
     })
     .pack()?;
 
@@ -482,6 +1108,57 @@ pub fn deposit(
     })
 }
 
+/// Creates a 'deposit' instruction which fails if less than `other_amount_min` of the
+/// other side would be deposited.
+pub fn deposit_with_min(
+    amm_program: &Pubkey,
+    amm_pool: &Pubkey,
+    amm_authority: &Pubkey,
+    amm_open_orders: &Pubkey,
+    amm_target_orders: &Pubkey,
+    amm_lp_mint: &Pubkey,
+    amm_coin_vault: &Pubkey,
+    amm_pc_vault: &Pubkey,
+    market: &Pubkey,
+    market_event_queue: &Pubkey,
+    user_token_coin: &Pubkey,
+    user_token_pc: &Pubkey,
+    user_token_lp: &Pubkey,
+    user_owner: &Pubkey,
+    max_coin_amount: u64,
+    max_pc_amount: u64,
+    base_side: u64,
+    other_amount_min: u64,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = deposit(
+        amm_program,
+        amm_pool,
+        amm_authority,
+        amm_open_orders,
+        amm_target_orders,
+        amm_lp_mint,
+        amm_coin_vault,
+        amm_pc_vault,
+        market,
+        market_event_queue,
+        user_token_coin,
+        user_token_pc,
+        user_token_lp,
+        user_owner,
+        max_coin_amount,
+        max_pc_amount,
+        base_side,
+    )?;
+    instruction.data = AmmInstruction::Deposit(DepositInstruction {
+        max_coin_amount,
+        max_pc_amount,
+        base_side,
+        other_amount_min: Some(other_amount_min),
+    })
+    .pack()?;
+    Ok(instruction)
+}
+
 /// Creates a 'withdraw' instruction.
 pub fn withdraw(
     amm_program: &Pubkey,
@@ -509,7 +1186,12 @@ pub fn withdraw(
 
     amount: u64,
 ) -> Result<Instruction, ProgramError> {
-    let data = AmmInstruction::Withdraw(WithdrawInstruction { amount }).pack()?;
+    let data = AmmInstruction::Withdraw(WithdrawInstruction {
+        amount,
+        min_coin_amount: None,
+        min_pc_amount: None,
+    })
+    .pack()?;
 
     let mut accounts = vec![
         // spl token
@@ -549,6 +1231,69 @@ pub fn withdraw(
     })
 }
 
+/// Creates a 'withdraw' instruction which fails if less than `min_coin_amount` or
+/// `min_pc_amount` would be withdrawn.
+pub fn withdraw_with_min(
+    amm_program: &Pubkey,
+    amm_pool: &Pubkey,
+    amm_authority: &Pubkey,
+    amm_open_orders: &Pubkey,
+    amm_target_orders: &Pubkey,
+    amm_lp_mint: &Pubkey,
+    amm_coin_vault: &Pubkey,
+    amm_pc_vault: &Pubkey,
+    market_program: &Pubkey,
+    market: &Pubkey,
+    market_coin_vault: &Pubkey,
+    market_pc_vault: &Pubkey,
+    market_vault_signer: &Pubkey,
+    user_token_lp: &Pubkey,
+    user_token_coin: &Pubkey,
+    user_token_pc: &Pubkey,
+    user_owner: &Pubkey,
+    market_event_queue: &Pubkey,
+    market_bids: &Pubkey,
+    market_asks: &Pubkey,
+
+    referrer_pc_account: Option<&Pubkey>,
+
+    amount: u64,
+    min_coin_amount: u64,
+    min_pc_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = withdraw(
+        amm_program,
+        amm_pool,
+        amm_authority,
+        amm_open_orders,
+        amm_target_orders,
+        amm_lp_mint,
+        amm_coin_vault,
+        amm_pc_vault,
+        market_program,
+        market,
+        market_coin_vault,
+        market_pc_vault,
+        market_vault_signer,
+        user_token_lp,
+        user_token_coin,
+        user_token_pc,
+        user_owner,
+        market_event_queue,
+        market_bids,
+        market_asks,
+        referrer_pc_account,
+        amount,
+    )?;
+    instruction.data = AmmInstruction::Withdraw(WithdrawInstruction {
+        amount,
+        min_coin_amount: Some(min_coin_amount),
+        min_pc_amount: Some(min_pc_amount),
+    })
+    .pack()?;
+    Ok(instruction)
+}
+
 /// Creates a 'swap base in' instruction.
 pub fn swap_base_in(
     amm_program: &Pubkey,
@@ -670,3 +1415,407 @@ pub fn swap_base_out(
         data,
     })
 }
+
+/// Creates a 'swap base in v2' instruction.
+pub fn swap_base_in_v2(
+    amm_program: &Pubkey,
+    amm_pool: &Pubkey,
+    amm_authority: &Pubkey,
+    amm_coin_vault: &Pubkey,
+    amm_pc_vault: &Pubkey,
+    user_token_source: &Pubkey,
+    user_token_destination: &Pubkey,
+    user_source_owner: &Pubkey,
+
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = AmmInstruction::SwapBaseInV2(SwapInstructionBaseIn {
+        amount_in,
+        minimum_amount_out,
+    })
+    .pack()?;
+
+    let accounts = vec![
+        // spl token
+        AccountMeta::new_readonly(spl_token::id(), false),
+        // amm
+        AccountMeta::new(*amm_pool, false),
+        AccountMeta::new_readonly(*amm_authority, false),
+        AccountMeta::new(*amm_coin_vault, false),
+        AccountMeta::new(*amm_pc_vault, false),
+        // user
+        AccountMeta::new(*user_token_source, false),
+        AccountMeta::new(*user_token_destination, false),
+        AccountMeta::new_readonly(*user_source_owner, true),
+    ];
+
+    Ok(Instruction {
+        program_id: *amm_program,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'swap base out v2' instruction.
+pub fn swap_base_out_v2(
+    amm_program: &Pubkey,
+    amm_pool: &Pubkey,
+    amm_authority: &Pubkey,
+    amm_coin_vault: &Pubkey,
+    amm_pc_vault: &Pubkey,
+    user_token_source: &Pubkey,
+    user_token_destination: &Pubkey,
+    user_source_owner: &Pubkey,
+
+    max_amount_in: u64,
+    amount_out: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = AmmInstruction::SwapBaseOutV2(SwapInstructionBaseOut {
+        max_amount_in,
+        amount_out,
+    })
+    .pack()?;
+
+    let accounts = vec![
+        // spl token
+        AccountMeta::new_readonly(spl_token::id(), false),
+        // amm
+        AccountMeta::new(*amm_pool, false),
+        AccountMeta::new_readonly(*amm_authority, false),
+        AccountMeta::new(*amm_coin_vault, false),
+        AccountMeta::new(*amm_pc_vault, false),
+        // user
+        AccountMeta::new(*user_token_source, false),
+        AccountMeta::new(*user_token_destination, false),
+        AccountMeta::new_readonly(*user_source_owner, true),
+    ];
+
+    Ok(Instruction {
+        program_id: *amm_program,
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn fees() -> Fees {
+        Fees {
+            min_separate_numerator: 5,
+            min_separate_denominator: 10_000,
+            trade_fee_numerator: 25,
+            trade_fee_denominator: 10_000,
+            pnl_numerator: 12,
+            pnl_denominator: 100,
+            swap_fee_numerator: 25,
+            swap_fee_denominator: 10_000,
+        }
+    }
+
+    #[allow(deprecated)]
+    fn all_instructions() -> Vec<AmmInstruction> {
+        let key = Pubkey::new_unique();
+        let swap_base_in = SwapInstructionBaseIn {
+            amount_in: 1_000_000,
+            minimum_amount_out: 990_000,
+        };
+        let swap_base_out = SwapInstructionBaseOut {
+            max_amount_in: 1_010_000,
+            amount_out: 1_000_000,
+        };
+        let mut instructions = vec![
+            AmmInstruction::Initialize(InitializeInstruction {
+                nonce: 254,
+                open_time: 1_700_000_000,
+            }),
+            AmmInstruction::Initialize2(InitializeInstruction2 {
+                nonce: 254,
+                open_time: 1_700_000_000,
+                init_pc_amount: 2_000_000,
+                init_coin_amount: 1_000_000,
+            }),
+            AmmInstruction::MonitorStep(MonitorStepInstruction {
+                plan_order_limit: 1,
+                place_order_limit: 2,
+                cancel_order_limit: 3,
+            }),
+            AmmInstruction::Deposit(DepositInstruction {
+                max_coin_amount: 1_000,
+                max_pc_amount: 2_000,
+                base_side: BaseSide::Coin.into(),
+                other_amount_min: None,
+            }),
+            AmmInstruction::Deposit(DepositInstruction {
+                max_coin_amount: 1_000,
+                max_pc_amount: 2_000,
+                base_side: BaseSide::Pc.into(),
+                other_amount_min: Some(990),
+            }),
+            AmmInstruction::Withdraw(WithdrawInstruction {
+                amount: 5_000,
+                min_coin_amount: None,
+                min_pc_amount: None,
+            }),
+            AmmInstruction::Withdraw(WithdrawInstruction {
+                amount: 5_000,
+                min_coin_amount: Some(100),
+                min_pc_amount: Some(200),
+            }),
+            AmmInstruction::MigrateToOpenBook,
+        ];
+        for param in 0..=17u8 {
+            let mut set_params = SetParamsInstruction {
+                param,
+                ..Default::default()
+            };
+            match AmmParams::from_u64(param as u64).unwrap() {
+                AmmParams::AmmOwner => set_params.new_pubkey = Some(key),
+                AmmParams::Fees => set_params.fees = Some(fees()),
+                AmmParams::LastOrderDistance => {
+                    set_params.last_order_distance = Some(LastOrderDistance {
+                        last_order_numerator: 1,
+                        last_order_denominator: 3,
+                    })
+                }
+                _ => set_params.value = Some(param as u64 * 7),
+            }
+            instructions.push(AmmInstruction::SetParams(set_params));
+        }
+        instructions.extend([
+            AmmInstruction::WithdrawPnl,
+            AmmInstruction::WithdrawSrm(WithdrawSrmInstruction { amount: 7 }),
+            AmmInstruction::SwapBaseIn(swap_base_in),
+            AmmInstruction::PreInitialize(PreInitializeInstruction { nonce: 253 }),
+            AmmInstruction::SwapBaseOut(swap_base_out),
+            AmmInstruction::SimulateInfo(SimulateInstruction {
+                param: SimulateParams::PoolInfo as u8,
+                ..Default::default()
+            }),
+            AmmInstruction::SimulateInfo(SimulateInstruction {
+                param: SimulateParams::SwapBaseInInfo as u8,
+                swap_base_in_value: Some(swap_base_in),
+                ..Default::default()
+            }),
+            AmmInstruction::SimulateInfo(SimulateInstruction {
+                param: SimulateParams::SwapBaseOutInfo as u8,
+                swap_base_out_value: Some(swap_base_out),
+                ..Default::default()
+            }),
+            AmmInstruction::SimulateInfo(SimulateInstruction {
+                param: SimulateParams::RunCrankInfo as u8,
+                ..Default::default()
+            }),
+            AmmInstruction::AdminCancelOrders(AdminCancelOrdersInstruction { limit: 9 }),
+            AmmInstruction::CreateConfigAccount,
+            AmmInstruction::UpdateConfigAccount(ConfigArgs {
+                param: 0,
+                owner: Some(key),
+                ..Default::default()
+            }),
+            AmmInstruction::UpdateConfigAccount(ConfigArgs {
+                param: 1,
+                owner: Some(key),
+                ..Default::default()
+            }),
+            AmmInstruction::UpdateConfigAccount(ConfigArgs {
+                param: 2,
+                create_pool_fee: Some(150_000_000),
+                ..Default::default()
+            }),
+            AmmInstruction::SwapBaseInV2(swap_base_in),
+            AmmInstruction::SwapBaseOutV2(swap_base_out),
+        ]);
+        instructions
+    }
+
+    #[test]
+    fn pack_unpack_round_trip() {
+        let instructions = all_instructions();
+        let mut tags = Vec::new();
+        for instruction in instructions {
+            let data = instruction.pack().unwrap();
+            tags.push(data[0]);
+            assert_eq!(AmmInstruction::unpack(&data).unwrap(), instruction);
+        }
+        tags.dedup();
+        assert_eq!(tags, (0..=17).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn pack_layout() {
+        let data = AmmInstruction::SwapBaseIn(SwapInstructionBaseIn {
+            amount_in: 1,
+            minimum_amount_out: 2,
+        })
+        .pack()
+        .unwrap();
+        assert_eq!(
+            data,
+            [9, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0].to_vec()
+        );
+        let data = AmmInstruction::SetParams(SetParamsInstruction {
+            param: AmmParams::Fees as u8,
+            fees: Some(fees()),
+            ..Default::default()
+        })
+        .pack()
+        .unwrap();
+        assert_eq!(data.len(), 2 + 8 * 8);
+    }
+
+    #[test]
+    fn unpack_rejects_unknown_tag() {
+        assert!(AmmInstruction::unpack(&[]).is_err());
+        assert!(AmmInstruction::unpack(&[18]).is_err());
+        assert!(AmmInstruction::unpack(&[u8::MAX, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        // Unknown params
+        assert!(AmmInstruction::unpack(&[6, 18, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        assert!(AmmInstruction::unpack(&[12, 4]).is_err());
+        assert!(AmmInstruction::unpack(&[15, 3, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn unpack_rejects_short_input() {
+        for instruction in all_instructions() {
+            let data = instruction.pack().unwrap();
+            for len in 1..data.len() {
+                let short = &data[..len];
+                // Deposit and Withdraw without the optional values are valid,
+                // a partial optional value is ignored like any other trailing bytes
+                if let Ok(unpacked) = AmmInstruction::unpack(short) {
+                    match (&instruction, unpacked) {
+                        (AmmInstruction::Deposit(_), AmmInstruction::Deposit(deposit)) => {
+                            assert!(len >= 25);
+                            assert_eq!(deposit.other_amount_min, None);
+                        }
+                        (AmmInstruction::Withdraw(_), AmmInstruction::Withdraw(withdraw)) => {
+                            assert!(len >= 9);
+                            assert_eq!(withdraw.min_coin_amount, None);
+                            assert_eq!(withdraw.min_pc_amount, None);
+                        }
+                        (instruction, unpacked) => {
+                            panic!(
+                                "{:?} unpacked from {:?} of {:?}",
+                                unpacked, short, instruction
+                            )
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unpack_ignores_trailing_bytes() {
+        for instruction in all_instructions() {
+            let mut data = instruction.pack().unwrap();
+            assert_eq!(AmmInstruction::unpack_strict(&data).unwrap(), instruction);
+            data.push(0);
+            assert_eq!(AmmInstruction::unpack(&data).unwrap(), instruction);
+            assert!(
+                AmmInstruction::unpack_strict(&data).is_err(),
+                "{:?} with a trailing byte",
+                instruction
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn swap_round_trip(amount_in: u64, minimum_amount_out: u64, max_amount_in: u64, amount_out: u64) {
+            let swap_base_in = SwapInstructionBaseIn { amount_in, minimum_amount_out };
+            let swap_base_out = SwapInstructionBaseOut { max_amount_in, amount_out };
+            for instruction in [
+                AmmInstruction::SwapBaseIn(swap_base_in),
+                AmmInstruction::SwapBaseOut(swap_base_out),
+                AmmInstruction::SwapBaseInV2(swap_base_in),
+                AmmInstruction::SwapBaseOutV2(swap_base_out),
+            ] {
+                let data = instruction.pack().unwrap();
+                prop_assert_eq!(AmmInstruction::unpack_strict(&data).unwrap(), instruction);
+            }
+        }
+
+        #[test]
+        fn deposit_round_trip(
+            max_coin_amount: u64,
+            max_pc_amount: u64,
+            base_side: u64,
+            other_amount_min: Option<u64>,
+        ) {
+            let instruction = AmmInstruction::Deposit(DepositInstruction {
+                max_coin_amount,
+                max_pc_amount,
+                base_side,
+                other_amount_min,
+            });
+            let data = instruction.pack().unwrap();
+            prop_assert_eq!(AmmInstruction::unpack_strict(&data).unwrap(), instruction);
+        }
+
+        #[test]
+        fn withdraw_round_trip(amount: u64, min_amounts: Option<(u64, u64)>) {
+            let instruction = AmmInstruction::Withdraw(WithdrawInstruction {
+                amount,
+                min_coin_amount: min_amounts.map(|(coin, _)| coin),
+                min_pc_amount: min_amounts.map(|(_, pc)| pc),
+            });
+            let data = instruction.pack().unwrap();
+            prop_assert_eq!(AmmInstruction::unpack_strict(&data).unwrap(), instruction);
+        }
+
+        #[test]
+        fn trailing_bytes_round_trip(
+            index in 0..all_instructions().len(),
+            trailing in prop::collection::vec(any::<u8>(), 1..64),
+        ) {
+            let instruction = all_instructions().swap_remove(index);
+            let mut data = instruction.pack().unwrap();
+            // Trailing bytes would be read as the missing Deposit and Withdraw optional values
+            let optional = matches!(
+                instruction,
+                AmmInstruction::Deposit(DepositInstruction { other_amount_min: None, .. })
+                    | AmmInstruction::Withdraw(WithdrawInstruction { min_coin_amount: None, .. })
+            );
+            prop_assume!(!optional);
+            data.extend(trailing);
+            prop_assert_eq!(AmmInstruction::unpack(&data).unwrap(), instruction);
+            prop_assert!(AmmInstruction::unpack_strict(&data).is_err());
+        }
+    }
+
+    #[test]
+    fn pack_rejects_mismatched_optional_values() {
+        assert!(AmmInstruction::SetParams(SetParamsInstruction {
+            param: AmmParams::AmmOwner as u8,
+            value: Some(1),
+            ..Default::default()
+        })
+        .pack()
+        .is_err());
+        assert!(AmmInstruction::SimulateInfo(SimulateInstruction {
+            param: SimulateParams::SwapBaseInInfo as u8,
+            ..Default::default()
+        })
+        .pack()
+        .is_err());
+        assert!(AmmInstruction::UpdateConfigAccount(ConfigArgs {
+            param: 2,
+            owner: Some(Pubkey::new_unique()),
+            ..Default::default()
+        })
+        .pack()
+        .is_err());
+        assert!(AmmInstruction::Withdraw(WithdrawInstruction {
+            amount: 1,
+            min_coin_amount: Some(1),
+            min_pc_amount: None,
+        })
+        .pack()
+        .is_err());
+    }
+}
@@ -0,0 +1,437 @@
+//! Decoder of the `ray_log` lines written by the AMM program.
+use anchor_lang::prelude::*;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Prefix of the log line written by the AMM program
+pub const RAY_LOG_PREFIX: &str = "ray_log: ";
+/// Prefix added by the runtime to `msg!` lines
+pub const PROGRAM_LOG_PREFIX: &str = "Program log: ";
+
+/// Type of a `ray_log`, the first byte of the log data
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogType {
+    /// Written by `Initialize2`
+    Init = 0,
+    /// Written by `Deposit`
+    Deposit = 1,
+    /// Written by `Withdraw`
+    Withdraw = 2,
+    /// Written by `SwapBaseIn` and `SwapBaseInV2`
+    SwapBaseIn = 3,
+    /// Written by `SwapBaseOut` and `SwapBaseOutV2`
+    SwapBaseOut = 4,
+}
+
+impl LogType {
+    /// Decode the log type, returns `None` on an unknown value
+    pub fn from_u8(log_type: u8) -> Option<Self> {
+        Some(match log_type {
+            0 => LogType::Init,
+            1 => LogType::Deposit,
+            2 => LogType::Withdraw,
+            3 => LogType::SwapBaseIn,
+            4 => LogType::SwapBaseOut,
+            _ => return None,
+        })
+    }
+
+    /// The raw value of the log type
+    pub fn into_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Log of a pool initialization
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InitLog {
+    /// Always `LogType::Init`
+    pub log_type: u8,
+    /// Open time of the pool
+    pub time: u64,
+    /// Pc decimals
+    pub pc_decimals: u8,
+    /// Coin decimals
+    pub coin_decimals: u8,
+    /// Pc lot size
+    pub pc_lot_size: u64,
+    /// Coin lot size
+    pub coin_lot_size: u64,
+    /// Initial pc amount
+    pub pc_amount: u64,
+    /// Initial coin amount
+    pub coin_amount: u64,
+    /// The OpenBook market
+    pub market: Pubkey,
+}
+
+/// Log of a deposit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositLog {
+    /// Always `LogType::Deposit`
+    pub log_type: u8,
+    /// Input max coin amount
+    pub max_coin: u64,
+    /// Input max pc amount
+    pub max_pc: u64,
+    /// Input base side, 0 for coin and 1 for pc
+    pub base: u64,
+    /// Pool coin amount
+    pub pool_coin: u64,
+    /// Pool pc amount
+    pub pool_pc: u64,
+    /// Pool lp amount
+    pub pool_lp: u64,
+    /// Pool pnl x
+    pub calc_pnl_x: u128,
+    /// Pool pnl y
+    pub calc_pnl_y: u128,
+    /// Coin amount deposited
+    pub deduct_coin: u64,
+    /// Pc amount deposited
+    pub deduct_pc: u64,
+    /// Lp amount minted
+    pub mint_lp: u64,
+}
+
+/// Log of a withdraw
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WithdrawLog {
+    /// Always `LogType::Withdraw`
+    pub log_type: u8,
+    /// Input lp amount
+    pub withdraw_lp: u64,
+    /// User lp amount
+    pub user_lp: u64,
+    /// Pool coin amount
+    pub pool_coin: u64,
+    /// Pool pc amount
+    pub pool_pc: u64,
+    /// Pool lp amount
+    pub pool_lp: u64,
+    /// Pool pnl x
+    pub calc_pnl_x: u128,
+    /// Pool pnl y
+    pub calc_pnl_y: u128,
+    /// Coin amount withdrawn
+    pub out_coin: u64,
+    /// Pc amount withdrawn
+    pub out_pc: u64,
+}
+
+/// Log of a swap base in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapBaseInLog {
+    /// Always `LogType::SwapBaseIn`
+    pub log_type: u8,
+    /// Input amount in
+    pub amount_in: u64,
+    /// Input minimum amount out
+    pub minimum_out: u64,
+    /// Swap direction, 1 for pc to coin and 2 for coin to pc
+    pub direction: u64,
+    /// User source token amount
+    pub user_source: u64,
+    /// Pool coin amount
+    pub pool_coin: u64,
+    /// Pool pc amount
+    pub pool_pc: u64,
+    /// Amount out
+    pub out_amount: u64,
+}
+
+/// Log of a swap base out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapBaseOutLog {
+    /// Always `LogType::SwapBaseOut`
+    pub log_type: u8,
+    /// Input max amount in
+    pub max_in: u64,
+    /// Input amount out
+    pub amount_out: u64,
+    /// Swap direction, 1 for pc to coin and 2 for coin to pc
+    pub direction: u64,
+    /// User source token amount
+    pub user_source: u64,
+    /// Pool coin amount
+    pub pool_coin: u64,
+    /// Pool pc amount
+    pub pool_pc: u64,
+    /// Amount in
+    pub deduct_in: u64,
+}
+
+/// All the `ray_log` types
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RayLog {
+    /// See `InitLog`
+    Init(InitLog),
+    /// See `DepositLog`
+    Deposit(DepositLog),
+    /// See `WithdrawLog`
+    Withdraw(WithdrawLog),
+    /// See `SwapBaseInLog`
+    SwapBaseIn(SwapBaseInLog),
+    /// See `SwapBaseOutLog`
+    SwapBaseOut(SwapBaseOutLog),
+}
+
+impl RayLog {
+    /// Decode a log from its data, returns `None` if the data is not a known log
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let mut data = data;
+        Some(match LogType::from_u8(*data.first()?)? {
+            LogType::Init => Self::Init(InitLog::deserialize(&mut data).ok()?),
+            LogType::Deposit => Self::Deposit(DepositLog::deserialize(&mut data).ok()?),
+            LogType::Withdraw => Self::Withdraw(WithdrawLog::deserialize(&mut data).ok()?),
+            LogType::SwapBaseIn => Self::SwapBaseIn(SwapBaseInLog::deserialize(&mut data).ok()?),
+            LogType::SwapBaseOut => Self::SwapBaseOut(SwapBaseOutLog::deserialize(&mut data).ok()?),
+        })
+    }
+
+    /// Decode a log from a `ray_log: <base64>` line, with or without the `Program log: ` prefix
+    pub fn from_log(log: &str) -> Option<Self> {
+        let log = log.strip_prefix(PROGRAM_LOG_PREFIX).unwrap_or(log);
+        let encoded = log.strip_prefix(RAY_LOG_PREFIX)?;
+        let data = STANDARD.decode(encoded.trim()).ok()?;
+        Self::decode(&data)
+    }
+
+    /// Decode all `ray_log` lines from the log messages of a transaction.
+    ///
+    /// Only lines written while the AMM program is the innermost invoked
+    /// program are decoded, so logs of other programs are skipped.
+    pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<Self> {
+        parse_program_logs(&crate::id(), logs, Self::from_log)
+    }
+}
+
+/// Decode the log lines written by `program_id` from the log messages of a transaction.
+///
+/// The invocation stack is tracked from the `Program <id> invoke`, `success` and
+/// `failed` lines, and `decode` is called on every other line written while
+/// `program_id` is the innermost invoked program. Lines of other programs, including
+/// programs invoked by `program_id` or invoking it, are skipped.
+fn parse_program_logs<T, S: AsRef<str>>(
+    program_id: &Pubkey,
+    logs: &[S],
+    mut decode: impl FnMut(&str) -> Option<T>,
+) -> Vec<T> {
+    let program_id = program_id.to_string();
+    // Whether each frame of the invocation stack is `program_id`
+    let mut invoke_stack: Vec<bool> = Vec::new();
+    let mut decoded = Vec::new();
+    for log in logs {
+        let log = log.as_ref();
+        let mut parts = log.split_whitespace();
+        if let (Some("Program"), Some(id), Some(status)) =
+            (parts.next(), parts.next(), parts.next())
+        {
+            // `Program log:`, `Program data:` and so on are written by the program
+            if !id.ends_with(':') {
+                if status == "invoke" {
+                    invoke_stack.push(id == program_id);
+                } else if status == "success" || status.starts_with("failed") {
+                    invoke_stack.pop();
+                }
+                continue;
+            }
+        }
+        if invoke_stack.last() == Some(&true) {
+            decoded.extend(decode(log));
+        }
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `ray_log` payloads of a SOL-USDC pool with 50k SOL and 7.5M USDC
+    const INIT_LOG: &str = "AADxU2UAAAAABgkBAAAAAAAAAEBCDwAAAAAAAPjVOtIGAAAAID2IeS0AAAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f";
+    const DEPOSIT_LOG: &str = "AQDKmjsAAAAAwBMACQAAAAAAAAAAAAAAAMDdLYh5LQAAgHO3OtIGAADv0j2UjgAAAAAAiLEWr+O1AgAAAAAAAAAAAG5nQ1oVaAAAAAAAAAAAAMqaOwAAAACB0fAIAAAAAJjhugAAAAAA";
+    const WITHDRAW_LOG: &str = "ApjhugAAAAAAmOG6AAAAAADA3S2IeS0AAIBztzrSBgAA79I9lI4AAAAAAIixFq/jtQIAAAAAAAAAAABuZ0NaFWgAAAAAAAAAANzJmjsAAAAAetHwCAAAAAA=";
+    const SWAP_BASE_IN_LOG: &str =
+        "AwDKmjsAAAAAQI/hCAAAAAACAAAAAAAAAADyBSoBAAAAwN0tiHktAACAc7c60gYAANoM6wgAAAAA";
+    const SWAP_BASE_OUT_LOG: &str =
+        "BIBgMzwAAAAAgNHwCAAAAAACAAAAAAAAAADyBSoBAAAAwN0tiHktAACAc7c60gYAAGVXwTsAAAAA";
+
+    const ROUTER_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+    fn decode(encoded: &str) -> Option<RayLog> {
+        RayLog::decode(&STANDARD.decode(encoded).unwrap())
+    }
+
+    fn swap_base_in_log() -> SwapBaseInLog {
+        SwapBaseInLog {
+            log_type: LogType::SwapBaseIn.into_u8(),
+            amount_in: 1_000_000_000,
+            minimum_out: 149_000_000,
+            direction: 2,
+            user_source: 5_000_000_000,
+            pool_coin: 49_999_999_000_000,
+            pool_pc: 7_499_998_000_000,
+            out_amount: 149_621_978,
+        }
+    }
+
+    #[test]
+    fn decode_init() {
+        assert_eq!(
+            decode(INIT_LOG),
+            Some(RayLog::Init(InitLog {
+                log_type: LogType::Init.into_u8(),
+                time: 1_700_000_000,
+                pc_decimals: 6,
+                coin_decimals: 9,
+                pc_lot_size: 1,
+                coin_lot_size: 1_000_000,
+                pc_amount: 7_500_000_000_000,
+                coin_amount: 50_000_000_000_000,
+                market: Pubkey::new_from_array(core::array::from_fn(|i| i as u8)),
+            }))
+        );
+    }
+
+    #[test]
+    fn decode_deposit() {
+        assert_eq!(
+            decode(DEPOSIT_LOG),
+            Some(RayLog::Deposit(DepositLog {
+                log_type: LogType::Deposit.into_u8(),
+                max_coin: 1_000_000_000,
+                max_pc: 151_000_000,
+                base: 0,
+                pool_coin: 49_999_999_000_000,
+                pool_pc: 7_499_998_000_000,
+                pool_lp: 612_372_435_695,
+                calc_pnl_x: 50_000_000_000_000_000_000,
+                calc_pnl_y: 7_500_000_000_000_000_000,
+                deduct_coin: 1_000_000_000,
+                deduct_pc: 150_000_001,
+                mint_lp: 12_247_448,
+            }))
+        );
+    }
+
+    #[test]
+    fn decode_withdraw() {
+        assert_eq!(
+            decode(WITHDRAW_LOG),
+            Some(RayLog::Withdraw(WithdrawLog {
+                log_type: LogType::Withdraw.into_u8(),
+                withdraw_lp: 12_247_448,
+                user_lp: 12_247_448,
+                pool_coin: 49_999_999_000_000,
+                pool_pc: 7_499_998_000_000,
+                pool_lp: 612_372_435_695,
+                calc_pnl_x: 50_000_000_000_000_000_000,
+                calc_pnl_y: 7_500_000_000_000_000_000,
+                out_coin: 999_999_964,
+                out_pc: 149_999_994,
+            }))
+        );
+    }
+
+    #[test]
+    fn decode_swap_base_in() {
+        assert_eq!(
+            decode(SWAP_BASE_IN_LOG),
+            Some(RayLog::SwapBaseIn(swap_base_in_log()))
+        );
+    }
+
+    #[test]
+    fn decode_swap_base_out() {
+        assert_eq!(
+            decode(SWAP_BASE_OUT_LOG),
+            Some(RayLog::SwapBaseOut(SwapBaseOutLog {
+                log_type: LogType::SwapBaseOut.into_u8(),
+                max_in: 1_010_000_000,
+                amount_out: 150_000_000,
+                direction: 2,
+                user_source: 5_000_000_000,
+                pool_coin: 49_999_999_000_000,
+                pool_pc: 7_499_998_000_000,
+                deduct_in: 1_002_526_565,
+            }))
+        );
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let mut data = STANDARD.decode(SWAP_BASE_IN_LOG).unwrap();
+        // Truncated
+        assert_eq!(RayLog::decode(&data[..data.len() - 1]), None);
+        assert_eq!(RayLog::decode(&[]), None);
+        // Unknown log type
+        data[0] = 5;
+        assert_eq!(RayLog::decode(&data), None);
+    }
+
+    #[test]
+    fn from_log_with_and_without_prefix() {
+        let swap_base_in = Some(RayLog::SwapBaseIn(swap_base_in_log()));
+        assert_eq!(
+            RayLog::from_log(&format!("Program log: ray_log: {}", SWAP_BASE_IN_LOG)),
+            swap_base_in
+        );
+        assert_eq!(
+            RayLog::from_log(&format!("ray_log: {}", SWAP_BASE_IN_LOG)),
+            swap_base_in
+        );
+        assert_eq!(
+            RayLog::from_log(&format!("Program data: {}", SWAP_BASE_IN_LOG)),
+            None
+        );
+        assert_eq!(RayLog::from_log("Program log: ray_log: !!!"), None);
+    }
+
+    #[test]
+    fn parse_logs_of_routed_swap() {
+        let program_id = crate::id().to_string();
+        let token_program_id = anchor_spl::token::ID.to_string();
+        let logs = vec![
+            format!("Program {} invoke [1]", ROUTER_PROGRAM_ID),
+            "Program log: Instruction: Route".to_string(),
+            // Not written by the AMM program
+            format!("Program log: ray_log: {}", SWAP_BASE_OUT_LOG),
+            format!("Program {} invoke [2]", program_id),
+            format!("Program log: ray_log: {}", SWAP_BASE_IN_LOG),
+            format!("Program {} invoke [3]", token_program_id),
+            "Program log: Instruction: Transfer".to_string(),
+            format!("Program log: ray_log: {}", SWAP_BASE_OUT_LOG),
+            format!(
+                "Program {} consumed 4645 of 1367019 compute units",
+                token_program_id
+            ),
+            format!("Program {} success", token_program_id),
+            format!("Program {} invoke [3]", token_program_id),
+            "Program log: Instruction: Transfer".to_string(),
+            format!(
+                "Program {} consumed 4736 of 1359437 compute units",
+                token_program_id
+            ),
+            format!("Program {} success", token_program_id),
+            format!(
+                "Program {} consumed 26503 of 1380346 compute units",
+                program_id
+            ),
+            format!("Program {} success", program_id),
+            format!("Program {} invoke [2]", program_id),
+            "Program log: ray_log: AwDK".to_string(),
+            format!("Program {} failed: custom program error: 0x1e", program_id),
+            format!("Program log: ray_log: {}", SWAP_BASE_OUT_LOG),
+            format!(
+                "Program {} consumed 60000 of 1400000 compute units",
+                ROUTER_PROGRAM_ID
+            ),
+            format!("Program {} success", ROUTER_PROGRAM_ID),
+        ];
+        assert_eq!(
+            RayLog::parse_logs(&logs),
+            vec![RayLog::SwapBaseIn(swap_base_in_log())]
+        );
+    }
+}
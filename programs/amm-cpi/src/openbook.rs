@@ -0,0 +1,346 @@
+//! Minimal account layouts of the OpenBook (serum v3) market program.
+use anchor_lang::{__private::bytemuck, error::ErrorCode, prelude::*};
+
+/// Padding at the start of all OpenBook accounts
+pub const ACCOUNT_HEAD_PADDING: &[u8; 5] = b"serum";
+/// Padding at the end of all OpenBook accounts
+pub const ACCOUNT_TAIL_PADDING: &[u8; 7] = b"padding";
+
+/// The market account of an OpenBook market, without the head padding
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Debug)]
+pub struct MarketState {
+    /// Initialized, Market
+    pub account_flags: u64,
+    /// The market account itself
+    pub own_address: Pubkey,
+    /// Nonce of the vault signer
+    pub vault_signer_nonce: u64,
+    /// Base mint
+    pub coin_mint: Pubkey,
+    /// Quote mint
+    pub pc_mint: Pubkey,
+    /// Base vault
+    pub coin_vault: Pubkey,
+    /// Base deposits total
+    pub coin_deposits_total: u64,
+    /// Base fees accrued
+    pub coin_fees_accrued: u64,
+    /// Quote vault
+    pub pc_vault: Pubkey,
+    /// Quote deposits total
+    pub pc_deposits_total: u64,
+    /// Quote fees accrued
+    pub pc_fees_accrued: u64,
+    /// Quote dust threshold
+    pub pc_dust_threshold: u64,
+    /// Request queue
+    pub req_q: Pubkey,
+    /// Event queue
+    pub event_q: Pubkey,
+    /// Bids
+    pub bids: Pubkey,
+    /// Asks
+    pub asks: Pubkey,
+    /// Base lot size
+    pub coin_lot_size: u64,
+    /// Quote lot size
+    pub pc_lot_size: u64,
+    /// Fee rate in bps
+    pub fee_rate_bps: u64,
+    /// Referrer rebates accrued
+    pub referrer_rebates_accrued: u64,
+}
+
+// Safe. The layout is packed without padding and all the fields are plain integers or keys.
+unsafe impl bytemuck::Zeroable for MarketState {}
+unsafe impl bytemuck::Pod for MarketState {}
+
+impl MarketState {
+    /// Size of the market state, without the head and tail paddings
+    pub const LEN: usize = 47 * 8;
+
+    /// Decode the market account, which must be owned by `market_program`
+    pub fn load_checked(market: &AccountInfo, market_program: &Pubkey) -> Result<Self> {
+        if market.owner != market_program {
+            return Err(Error::from(ErrorCode::AccountOwnedByWrongProgram)
+                .with_pubkeys((*market.owner, *market_program)));
+        }
+        let data = market.try_borrow_data()?;
+        let state = Self::unpack(&data)?;
+        let own_address = state.own_address;
+        require_keys_eq!(
+            own_address,
+            market.key(),
+            ErrorCode::AccountDidNotDeserialize
+        );
+        Ok(state)
+    }
+
+    /// Decode the data of a market account, including the head padding.
+    /// Markets with permissioned authorities store more fields after the state,
+    /// so only the minimal length is checked.
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        let state = data
+            .strip_prefix(ACCOUNT_HEAD_PADDING)
+            .filter(|state| state.len() >= Self::LEN + ACCOUNT_TAIL_PADDING.len())
+            .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+        Ok(bytemuck::pod_read_unaligned(&state[..Self::LEN]))
+    }
+
+    /// The vault signer of the market, derived from the market key and `vault_signer_nonce`
+    pub fn vault_signer(&self, market_program: &Pubkey) -> Result<Pubkey> {
+        let own_address = self.own_address;
+        let vault_signer_nonce = self.vault_signer_nonce;
+        Pubkey::create_program_address(
+            &[own_address.as_ref(), &vault_signer_nonce.to_le_bytes()],
+            market_program,
+        )
+        .map_err(|_| error!(ErrorCode::ConstraintSeeds))
+    }
+}
+
+const _: () = assert!(MarketState::LEN == std::mem::size_of::<MarketState>());
+
+/// The open orders account of an OpenBook market, without the head padding
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Debug)]
+pub struct OpenOrders {
+    /// Initialized, OpenOrders
+    pub account_flags: u64,
+    /// The market of the open orders
+    pub market: Pubkey,
+    /// The owner of the open orders, the amm authority for amm pools
+    pub owner: Pubkey,
+    /// Base amount which can be settled
+    pub native_coin_free: u64,
+    /// Base amount which can be settled or is locked in orders
+    pub native_coin_total: u64,
+    /// Quote amount which can be settled
+    pub native_pc_free: u64,
+    /// Quote amount which can be settled or is locked in orders
+    pub native_pc_total: u64,
+    /// Free order slots
+    pub free_slot_bits: u128,
+    /// Order sides
+    pub is_bid_bits: u128,
+    /// Order ids
+    pub orders: [u128; 128],
+    /// Client order ids
+    pub client_order_ids: [u64; 128],
+    /// Referrer rebates accrued
+    pub referrer_rebates_accrued: u64,
+}
+
+// Safe. The layout is packed without padding and all the fields are plain integers or keys.
+unsafe impl bytemuck::Zeroable for OpenOrders {}
+unsafe impl bytemuck::Pod for OpenOrders {}
+
+impl OpenOrders {
+    /// Size of the open orders, without the head and tail paddings
+    pub const LEN: usize = 8 + 32 + 32 + 4 * 8 + 16 * 2 + 16 * 128 + 8 * 128 + 8;
+
+    /// Decode the open orders account, which must be owned by `market_program`
+    pub fn load_checked(open_orders: &AccountInfo, market_program: &Pubkey) -> Result<Self> {
+        if open_orders.owner != market_program {
+            return Err(Error::from(ErrorCode::AccountOwnedByWrongProgram)
+                .with_pubkeys((*open_orders.owner, *market_program)));
+        }
+        let data = open_orders.try_borrow_data()?;
+        Self::unpack(&data)
+    }
+
+    /// Decode the data of an open orders account, including the head and tail paddings
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        let state = data
+            .strip_prefix(ACCOUNT_HEAD_PADDING)
+            .and_then(|state| state.strip_suffix(ACCOUNT_TAIL_PADDING))
+            .filter(|state| state.len() == Self::LEN)
+            .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+        Ok(bytemuck::pod_read_unaligned(state))
+    }
+}
+
+const _: () = assert!(OpenOrders::LEN == std::mem::size_of::<OpenOrders>());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_key(data: &mut [u8], offset: usize, key: &Pubkey) {
+        data[offset..offset + 32].copy_from_slice(key.as_ref());
+    }
+
+    fn market_data(state: &[u8]) -> Vec<u8> {
+        [ACCOUNT_HEAD_PADDING, state, ACCOUNT_TAIL_PADDING].concat()
+    }
+
+    fn vault_signer_nonce(market: &Pubkey, market_program: &Pubkey) -> u64 {
+        (0..)
+            .find(|nonce: &u64| {
+                Pubkey::create_program_address(
+                    &[market.as_ref(), &nonce.to_le_bytes()],
+                    market_program,
+                )
+                .is_ok()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn market_state_field_offsets() {
+        let keys: Vec<Pubkey> = (0..9).map(|_| Pubkey::new_unique()).collect();
+        let mut state = vec![0u8; MarketState::LEN];
+        write_u64(&mut state, 0, 3);
+        write_key(&mut state, 8, &keys[0]);
+        write_u64(&mut state, 40, 1);
+        write_key(&mut state, 48, &keys[1]);
+        write_key(&mut state, 80, &keys[2]);
+        write_key(&mut state, 112, &keys[3]);
+        write_key(&mut state, 160, &keys[4]);
+        write_key(&mut state, 216, &keys[5]);
+        write_key(&mut state, 248, &keys[6]);
+        write_key(&mut state, 280, &keys[7]);
+        write_key(&mut state, 312, &keys[8]);
+        write_u64(&mut state, 344, 1_000);
+        write_u64(&mut state, 352, 10);
+        write_u64(&mut state, 368, 7);
+
+        let market_state = MarketState::unpack(&market_data(&state)).unwrap();
+        let market_keys = [
+            market_state.own_address,
+            market_state.coin_mint,
+            market_state.pc_mint,
+            market_state.coin_vault,
+            market_state.pc_vault,
+            market_state.req_q,
+            market_state.event_q,
+            market_state.bids,
+            market_state.asks,
+        ];
+        assert_eq!(market_keys.to_vec(), keys);
+        let (account_flags, vault_signer_nonce) =
+            (market_state.account_flags, market_state.vault_signer_nonce);
+        assert_eq!((account_flags, vault_signer_nonce), (3, 1));
+        let (coin_lot_size, pc_lot_size, referrer_rebates_accrued) = (
+            market_state.coin_lot_size,
+            market_state.pc_lot_size,
+            market_state.referrer_rebates_accrued,
+        );
+        assert_eq!(
+            (coin_lot_size, pc_lot_size, referrer_rebates_accrued),
+            (1_000, 10, 7)
+        );
+
+        // Permissioned markets store more fields after the state
+        let mut data = market_data(&state);
+        data.extend([0u8; 64]);
+        assert_eq!(
+            MarketState::unpack(&data).unwrap().own_address,
+            market_state.own_address
+        );
+    }
+
+    #[test]
+    fn market_state_unpack_rejects_invalid_data() {
+        let state = vec![0u8; MarketState::LEN];
+        for data in [
+            vec![],
+            state.clone(),
+            market_data(&state[1..]),
+            [b"serun".as_ref(), &state, ACCOUNT_TAIL_PADDING].concat(),
+        ] {
+            assert_eq!(
+                MarketState::unpack(&data).unwrap_err(),
+                ErrorCode::AccountDidNotDeserialize.into()
+            );
+        }
+    }
+
+    #[test]
+    fn market_state_load_checked() {
+        let market = Pubkey::new_unique();
+        let market_program = crate::openbook_program_id::id();
+        let mut state = vec![0u8; MarketState::LEN];
+        write_key(&mut state, 8, &market);
+        let mut data = market_data(&state);
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &market,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &market_program,
+            false,
+            0,
+        );
+        assert!(MarketState::load_checked(&account, &market_program).is_ok());
+        assert_eq!(
+            MarketState::load_checked(&account, &crate::id()).unwrap_err(),
+            ErrorCode::AccountOwnedByWrongProgram.into()
+        );
+
+        let other = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = market_data(&state);
+        let account = AccountInfo::new(
+            &other,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &market_program,
+            false,
+            0,
+        );
+        assert_eq!(
+            MarketState::load_checked(&account, &market_program).unwrap_err(),
+            ErrorCode::AccountDidNotDeserialize.into()
+        );
+    }
+
+    #[test]
+    fn vault_signer_derivation() {
+        let market = Pubkey::new_unique();
+        let market_program = crate::openbook_program_id::id();
+        let nonce = vault_signer_nonce(&market, &market_program);
+        let mut state = vec![0u8; MarketState::LEN];
+        write_key(&mut state, 8, &market);
+        write_u64(&mut state, 40, nonce);
+        let market_state = MarketState::unpack(&market_data(&state)).unwrap();
+        let vault_signer = market_state.vault_signer(&market_program).unwrap();
+        assert_eq!(
+            vault_signer,
+            Pubkey::create_program_address(
+                &[market.as_ref(), &nonce.to_le_bytes()],
+                &market_program
+            )
+            .unwrap()
+        );
+        assert!(!vault_signer.is_on_curve());
+
+        // A nonce whose address is on the curve is rejected
+        let on_curve = (0..)
+            .find(|nonce: &u64| {
+                Pubkey::create_program_address(
+                    &[market.as_ref(), &nonce.to_le_bytes()],
+                    &market_program,
+                )
+                .is_err()
+            })
+            .unwrap();
+        write_u64(&mut state, 40, on_curve);
+        let market_state = MarketState::unpack(&market_data(&state)).unwrap();
+        assert_eq!(
+            market_state.vault_signer(&market_program).unwrap_err(),
+            ErrorCode::ConstraintSeeds.into()
+        );
+    }
+}
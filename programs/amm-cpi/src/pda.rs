@@ -0,0 +1,69 @@
+//! Program derived addresses of the Raydium AMM program.
+use anchor_lang::prelude::*;
+
+/// Seed of the amm authority, which owns the vaults and the lp mint of all pools
+pub const AUTHORITY_AMM: &[u8] = b"amm authority";
+/// Seed of the amm account of a pool
+pub const AMM_ASSOCIATED_SEED: &[u8] = b"amm_associated_seed";
+/// Seed of the target orders account of a pool
+pub const TARGET_ASSOCIATED_SEED: &[u8] = b"target_associated_seed";
+/// Seed of the open orders account of a pool
+pub const OPEN_ORDER_ASSOCIATED_SEED: &[u8] = b"open_order_associated_seed";
+/// Seed of the coin vault of a pool
+pub const COIN_VAULT_ASSOCIATED_SEED: &[u8] = b"coin_vault_associated_seed";
+/// Seed of the pc vault of a pool
+pub const PC_VAULT_ASSOCIATED_SEED: &[u8] = b"pc_vault_associated_seed";
+/// Seed of the lp mint of a pool
+pub const LP_MINT_ASSOCIATED_SEED: &[u8] = b"lp_mint_associated_seed";
+/// Seed of the config account of the amm program
+pub const AMM_CONFIG_SEED: &[u8] = b"amm_config_account_seed";
+
+/// Derives a pool account with seed = [program_id, market, seed]
+pub fn get_associated_address(market: &Pubkey, seed: &[u8]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[crate::id().as_ref(), market.as_ref(), seed], &crate::id())
+}
+
+/// Derives the amm account of the pool of `market`
+pub fn get_amm_address(market: &Pubkey) -> (Pubkey, u8) {
+    get_associated_address(market, AMM_ASSOCIATED_SEED)
+}
+
+/// Derives the target orders account of the pool of `market`
+pub fn get_target_orders_address(market: &Pubkey) -> (Pubkey, u8) {
+    get_associated_address(market, TARGET_ASSOCIATED_SEED)
+}
+
+/// Derives the open orders account of the pool of `market`
+pub fn get_open_orders_address(market: &Pubkey) -> (Pubkey, u8) {
+    get_associated_address(market, OPEN_ORDER_ASSOCIATED_SEED)
+}
+
+/// Derives the coin vault of the pool of `market`
+pub fn get_coin_vault_address(market: &Pubkey) -> (Pubkey, u8) {
+    get_associated_address(market, COIN_VAULT_ASSOCIATED_SEED)
+}
+
+/// Derives the pc vault of the pool of `market`
+pub fn get_pc_vault_address(market: &Pubkey) -> (Pubkey, u8) {
+    get_associated_address(market, PC_VAULT_ASSOCIATED_SEED)
+}
+
+/// Derives the lp mint of the pool of `market`
+pub fn get_lp_mint_address(market: &Pubkey) -> (Pubkey, u8) {
+    get_associated_address(market, LP_MINT_ASSOCIATED_SEED)
+}
+
+/// Derives the amm authority with seed = [b"amm authority"]
+pub fn get_amm_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTHORITY_AMM], &crate::id())
+}
+
+/// The nonce of the amm authority, which is the `nonce` argument of `initialize`
+pub fn get_amm_authority_nonce() -> u8 {
+    get_amm_authority().1
+}
+
+/// Derives the config account of the amm program with seed = [b"amm_config_account_seed"]
+pub fn get_amm_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AMM_CONFIG_SEED], &crate::id())
+}
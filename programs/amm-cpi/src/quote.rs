@@ -0,0 +1,569 @@
+//! Swap quoting with the same math as the AMM program.
+use crate::{library::BaseSide, openbook::OpenOrders, states::AmmInfo};
+
+/// The direction of a swap
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapDirection {
+    /// Input pc, output coin
+    PC2Coin = 1,
+    /// Input coin, output pc
+    Coin2PC = 2,
+}
+
+/// Result of a quoted swap
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Amount of the input token, including `swap_fee`
+    pub amount_in: u64,
+    /// Amount of the output token
+    pub amount_out: u64,
+    /// Swap fee charged in the input token
+    pub swap_fee: u64,
+}
+
+/// Ceiling division as the AMM program does it, a quotient below one is rounded half up
+fn checked_ceil_div(numerator: u128, denominator: u128) -> Option<u128> {
+    let quotient = numerator.checked_div(denominator)?;
+    if quotient == 0 {
+        return Some(if numerator.checked_mul(2)? >= denominator {
+            1
+        } else {
+            0
+        });
+    }
+    if numerator.checked_rem(denominator)? > 0 {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+/// Pool reserves of pc and coin, as `(total_pc, total_coin)`.
+///
+/// When the pool places orders on the market, the open orders totals are added to the
+/// vault balances. Fills in the market event queue which are not consumed yet are not
+/// counted, so the quote may differ from the program until the event queue is cranked.
+///
+/// # Arguments
+///
+/// * `amm` - The amm account
+/// * `open_orders` - The open orders account of the amm
+/// * `coin_vault_amount` - The amount of `amm.coin_vault`
+/// * `pc_vault_amount` - The amount of `amm.pc_vault`
+///
+pub fn calc_total_without_take_pnl(
+    amm: &AmmInfo,
+    open_orders: &OpenOrders,
+    coin_vault_amount: u64,
+    pc_vault_amount: u64,
+) -> Option<(u64, u64)> {
+    let enable_orderbook = amm.amm_status()?.orderbook_permission();
+    let (total_pc, total_coin) = if enable_orderbook {
+        (
+            pc_vault_amount.checked_add(open_orders.native_pc_total)?,
+            coin_vault_amount.checked_add(open_orders.native_coin_total)?,
+        )
+    } else {
+        (pc_vault_amount, coin_vault_amount)
+    };
+    let (need_take_pnl_coin, need_take_pnl_pc) = amm.need_take_pnl();
+    Some((
+        total_pc.checked_sub(need_take_pnl_pc)?,
+        total_coin.checked_sub(need_take_pnl_coin)?,
+    ))
+}
+
+/// Output amount of a constant product swap without fees, rounded down
+pub fn swap_token_amount_base_in(
+    amount_in: u128,
+    total_pc_without_take_pnl: u128,
+    total_coin_without_take_pnl: u128,
+    swap_direction: SwapDirection,
+) -> Option<u128> {
+    let (input_total, output_total) = match swap_direction {
+        SwapDirection::Coin2PC => (total_coin_without_take_pnl, total_pc_without_take_pnl),
+        SwapDirection::PC2Coin => (total_pc_without_take_pnl, total_coin_without_take_pnl),
+    };
+    output_total
+        .checked_mul(amount_in)?
+        .checked_div(input_total.checked_add(amount_in)?)
+}
+
+/// Input amount of a constant product swap without fees, rounded up
+pub fn swap_token_amount_base_out(
+    amount_out: u128,
+    total_pc_without_take_pnl: u128,
+    total_coin_without_take_pnl: u128,
+    swap_direction: SwapDirection,
+) -> Option<u128> {
+    let (input_total, output_total) = match swap_direction {
+        SwapDirection::Coin2PC => (total_coin_without_take_pnl, total_pc_without_take_pnl),
+        SwapDirection::PC2Coin => (total_pc_without_take_pnl, total_coin_without_take_pnl),
+    };
+    checked_ceil_div(
+        input_total.checked_mul(amount_out)?,
+        output_total.checked_sub(amount_out)?,
+    )
+}
+
+/// Quote a `SwapBaseIn` or `SwapBaseInV2`
+///
+/// # Arguments
+///
+/// * `amm` - The amm account
+/// * `open_orders` - The open orders account of the amm
+/// * `coin_vault_amount` - The amount of `amm.coin_vault`
+/// * `pc_vault_amount` - The amount of `amm.pc_vault`
+/// * `swap_direction` - The direction of the swap
+/// * `amount_in` - The input amount, including the swap fee
+///
+pub fn swap_base_in(
+    amm: &AmmInfo,
+    open_orders: &OpenOrders,
+    coin_vault_amount: u64,
+    pc_vault_amount: u64,
+    swap_direction: SwapDirection,
+    amount_in: u64,
+) -> Option<SwapQuote> {
+    let (total_pc, total_coin) =
+        calc_total_without_take_pnl(amm, open_orders, coin_vault_amount, pc_vault_amount)?;
+    let fees = amm.fees();
+    let swap_fee = checked_ceil_div(
+        u128::from(amount_in).checked_mul(u128::from(fees.swap_fee_numerator))?,
+        u128::from(fees.swap_fee_denominator),
+    )?;
+    let swap_in_after_deduct_fee = u128::from(amount_in).checked_sub(swap_fee)?;
+    let amount_out = swap_token_amount_base_in(
+        swap_in_after_deduct_fee,
+        u128::from(total_pc),
+        u128::from(total_coin),
+        swap_direction,
+    )?;
+    Some(SwapQuote {
+        amount_in,
+        amount_out: u64::try_from(amount_out).ok()?,
+        swap_fee: u64::try_from(swap_fee).ok()?,
+    })
+}
+
+/// Quote a `SwapBaseOut` or `SwapBaseOutV2`
+///
+/// # Arguments
+///
+/// * `amm` - The amm account
+/// * `open_orders` - The open orders account of the amm
+/// * `coin_vault_amount` - The amount of `amm.coin_vault`
+/// * `pc_vault_amount` - The amount of `amm.pc_vault`
+/// * `swap_direction` - The direction of the swap
+/// * `amount_out` - The output amount
+///
+pub fn swap_base_out(
+    amm: &AmmInfo,
+    open_orders: &OpenOrders,
+    coin_vault_amount: u64,
+    pc_vault_amount: u64,
+    swap_direction: SwapDirection,
+    amount_out: u64,
+) -> Option<SwapQuote> {
+    let (total_pc, total_coin) =
+        calc_total_without_take_pnl(amm, open_orders, coin_vault_amount, pc_vault_amount)?;
+    let fees = amm.fees();
+    let swap_in_before_add_fee = swap_token_amount_base_out(
+        u128::from(amount_out),
+        u128::from(total_pc),
+        u128::from(total_coin),
+        swap_direction,
+    )?;
+    let swap_in_after_add_fee = checked_ceil_div(
+        swap_in_before_add_fee.checked_mul(u128::from(fees.swap_fee_denominator))?,
+        u128::from(fees.swap_fee_denominator).checked_sub(u128::from(fees.swap_fee_numerator))?,
+    )?;
+    let swap_fee = swap_in_after_add_fee.checked_sub(swap_in_before_add_fee)?;
+    Some(SwapQuote {
+        amount_in: u64::try_from(swap_in_after_add_fee).ok()?,
+        amount_out,
+        swap_fee: u64::try_from(swap_fee).ok()?,
+    })
+}
+
+/// Amounts of a deposit, as the `deposit` arguments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositAmounts {
+    /// Max coin amount, exact if `base_side` is coin
+    pub max_coin_amount: u64,
+    /// Max pc amount, exact if `base_side` is pc
+    pub max_pc_amount: u64,
+    /// The fixed side
+    pub base_side: BaseSide,
+    /// Estimated lp amount minted, without slippage
+    pub lp_amount: u64,
+}
+
+/// Compute the `deposit` arguments for a fixed amount of one side.
+///
+/// The other side is the amount the program would take at the current reserves,
+/// increased by `slippage_bps`. The program takes the pending pnl before the
+/// deposit, so the lp amount is an estimate.
+///
+/// # Arguments
+///
+/// * `amm` - The amm account
+/// * `open_orders` - The open orders account of the amm
+/// * `coin_vault_amount` - The amount of `amm.coin_vault`
+/// * `pc_vault_amount` - The amount of `amm.pc_vault`
+/// * `base_side` - The fixed side
+/// * `base_amount` - The amount of the fixed side
+/// * `slippage_bps` - Tolerance of the other side, in bps
+///
+pub fn deposit_amounts(
+    amm: &AmmInfo,
+    open_orders: &OpenOrders,
+    coin_vault_amount: u64,
+    pc_vault_amount: u64,
+    base_side: BaseSide,
+    base_amount: u64,
+    slippage_bps: u64,
+) -> Option<DepositAmounts> {
+    let (total_pc, total_coin) =
+        calc_total_without_take_pnl(amm, open_orders, coin_vault_amount, pc_vault_amount)?;
+    let (total_pc, total_coin) = (u128::from(total_pc), u128::from(total_coin));
+    let (deduct_coin_amount, deduct_pc_amount) = match base_side {
+        BaseSide::Coin => (
+            u128::from(base_amount),
+            checked_ceil_div(u128::from(base_amount).checked_mul(total_pc)?, total_coin)?,
+        ),
+        BaseSide::Pc => (
+            checked_ceil_div(u128::from(base_amount).checked_mul(total_coin)?, total_pc)?,
+            u128::from(base_amount),
+        ),
+    };
+    let with_slippage = |amount: u128| {
+        amount
+            .checked_mul(u128::from(10_000u64.checked_add(slippage_bps)?))?
+            .checked_div(10_000)
+    };
+    let (max_coin_amount, max_pc_amount) = match base_side {
+        BaseSide::Coin => (deduct_coin_amount, with_slippage(deduct_pc_amount)?),
+        BaseSide::Pc => (with_slippage(deduct_coin_amount)?, deduct_pc_amount),
+    };
+    let lp_amount = match base_side {
+        BaseSide::Coin => deduct_coin_amount
+            .checked_mul(u128::from(amm.lp_amount))?
+            .checked_div(total_coin)?,
+        BaseSide::Pc => deduct_pc_amount
+            .checked_mul(u128::from(amm.lp_amount))?
+            .checked_div(total_pc)?,
+    };
+    Some(DepositAmounts {
+        max_coin_amount: u64::try_from(max_coin_amount).ok()?,
+        max_pc_amount: u64::try_from(max_pc_amount).ok()?,
+        base_side,
+        lp_amount: u64::try_from(lp_amount).ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::AmmStatus;
+    use anchor_lang::__private::bytemuck::Zeroable;
+
+    // A SOL-USDC pool with 50k SOL and 7.5M USDC in the vaults
+    const COIN_VAULT_AMOUNT: u64 = 50_000_000_000_000;
+    const PC_VAULT_AMOUNT: u64 = 7_500_000_000_000;
+
+    fn amm(status: AmmStatus) -> AmmInfo {
+        let mut amm = AmmInfo::zeroed();
+        amm.status = status as u64;
+        amm.fees.trade_fee_numerator = 25;
+        amm.fees.trade_fee_denominator = 10_000;
+        amm.fees.swap_fee_numerator = 25;
+        amm.fees.swap_fee_denominator = 10_000;
+        amm.state_data.need_take_pnl_coin = 1_000_000;
+        amm.state_data.need_take_pnl_pc = 2_000_000;
+        amm
+    }
+
+    fn open_orders() -> OpenOrders {
+        let mut open_orders = OpenOrders::zeroed();
+        open_orders.native_coin_total = 10_000_000_000;
+        open_orders.native_pc_total = 1_500_000_000;
+        open_orders
+    }
+
+    #[test]
+    fn calc_total_without_take_pnl_without_orderbook() {
+        for status in [AmmStatus::SwapOnly, AmmStatus::LiquidityOnly] {
+            assert_eq!(
+                calc_total_without_take_pnl(
+                    &amm(status),
+                    &open_orders(),
+                    COIN_VAULT_AMOUNT,
+                    PC_VAULT_AMOUNT
+                ),
+                Some((7_499_998_000_000, 49_999_999_000_000))
+            );
+        }
+    }
+
+    #[test]
+    fn calc_total_without_take_pnl_with_orderbook() {
+        for status in [
+            AmmStatus::Initialized,
+            AmmStatus::OrderBookOnly,
+            AmmStatus::WaitingTrade,
+        ] {
+            assert_eq!(
+                calc_total_without_take_pnl(
+                    &amm(status),
+                    &open_orders(),
+                    COIN_VAULT_AMOUNT,
+                    PC_VAULT_AMOUNT
+                ),
+                Some((7_501_498_000_000, 50_009_999_000_000))
+            );
+        }
+    }
+
+    #[test]
+    fn calc_total_without_take_pnl_invalid() {
+        let mut amm = amm(AmmStatus::SwapOnly);
+        assert_eq!(
+            calc_total_without_take_pnl(&amm, &open_orders(), 999_999, PC_VAULT_AMOUNT),
+            None
+        );
+        amm.status = 8;
+        assert_eq!(
+            calc_total_without_take_pnl(&amm, &open_orders(), COIN_VAULT_AMOUNT, PC_VAULT_AMOUNT),
+            None
+        );
+    }
+
+    #[test]
+    fn swap_base_in_without_orderbook() {
+        let amm = amm(AmmStatus::SwapOnly);
+        let open_orders = open_orders();
+        assert_eq!(
+            swap_base_in(
+                &amm,
+                &open_orders,
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                SwapDirection::Coin2PC,
+                1_000_000_000
+            ),
+            Some(SwapQuote {
+                amount_in: 1_000_000_000,
+                amount_out: 149_621_978,
+                swap_fee: 2_500_000,
+            })
+        );
+        assert_eq!(
+            swap_base_in(
+                &amm,
+                &open_orders,
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                SwapDirection::PC2Coin,
+                150_000_000
+            ),
+            Some(SwapQuote {
+                amount_in: 150_000_000,
+                amount_out: 997_480_346,
+                swap_fee: 375_000,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_in_with_orderbook() {
+        let amm = amm(AmmStatus::Initialized);
+        let open_orders = open_orders();
+        assert_eq!(
+            swap_base_in(
+                &amm,
+                &open_orders,
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                SwapDirection::PC2Coin,
+                150_000_000
+            ),
+            Some(SwapQuote {
+                amount_in: 150_000_000,
+                amount_out: 997_480_350,
+                swap_fee: 375_000,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_in_dust() {
+        // A fee below one half is rounded down to zero
+        assert_eq!(
+            swap_base_in(
+                &amm(AmmStatus::SwapOnly),
+                &open_orders(),
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                SwapDirection::PC2Coin,
+                1
+            ),
+            Some(SwapQuote {
+                amount_in: 1,
+                amount_out: 6,
+                swap_fee: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_out_without_orderbook() {
+        let amm = amm(AmmStatus::SwapOnly);
+        let open_orders = open_orders();
+        assert_eq!(
+            swap_base_out(
+                &amm,
+                &open_orders,
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                SwapDirection::Coin2PC,
+                150_000_000
+            ),
+            Some(SwapQuote {
+                amount_in: 1_002_526_565,
+                amount_out: 150_000_000,
+                swap_fee: 2_506_317,
+            })
+        );
+        assert_eq!(
+            swap_base_out(
+                &amm,
+                &open_orders,
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                SwapDirection::PC2Coin,
+                1_000_000_000
+            ),
+            Some(SwapQuote {
+                amount_in: 150_378_912,
+                amount_out: 1_000_000_000,
+                swap_fee: 375_948,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_out_with_orderbook() {
+        let amm = amm(AmmStatus::Initialized);
+        let open_orders = open_orders();
+        assert_eq!(
+            swap_base_out(
+                &amm,
+                &open_orders,
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                SwapDirection::Coin2PC,
+                150_000_000
+            ),
+            Some(SwapQuote {
+                amount_in: 1_002_526_561,
+                amount_out: 150_000_000,
+                swap_fee: 2_506_317,
+            })
+        );
+        assert_eq!(
+            swap_base_out(
+                &amm,
+                &open_orders,
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                SwapDirection::PC2Coin,
+                1_000_000_000
+            ),
+            Some(SwapQuote {
+                amount_in: 150_378_911,
+                amount_out: 1_000_000_000,
+                swap_fee: 375_948,
+            })
+        );
+    }
+
+    #[test]
+    fn swap_base_out_exceeding_reserves() {
+        assert_eq!(
+            swap_base_out(
+                &amm(AmmStatus::SwapOnly),
+                &open_orders(),
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                SwapDirection::Coin2PC,
+                PC_VAULT_AMOUNT
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn deposit_amounts_base_coin() {
+        let mut amm = amm(AmmStatus::SwapOnly);
+        amm.lp_amount = 612_372_435_695;
+        assert_eq!(
+            deposit_amounts(
+                &amm,
+                &open_orders(),
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                BaseSide::Coin,
+                1_000_000_000,
+                50
+            ),
+            Some(DepositAmounts {
+                max_coin_amount: 1_000_000_000,
+                max_pc_amount: 150_749_962,
+                base_side: BaseSide::Coin,
+                lp_amount: 12_247_448,
+            })
+        );
+    }
+
+    #[test]
+    fn deposit_amounts_base_pc() {
+        let mut amm = amm(AmmStatus::SwapOnly);
+        amm.lp_amount = 612_372_435_695;
+        assert_eq!(
+            deposit_amounts(
+                &amm,
+                &open_orders(),
+                COIN_VAULT_AMOUNT,
+                PC_VAULT_AMOUNT,
+                BaseSide::Pc,
+                150_000_000,
+                50
+            ),
+            Some(DepositAmounts {
+                max_coin_amount: 1_005_000_248,
+                max_pc_amount: 150_000_000,
+                base_side: BaseSide::Pc,
+                lp_amount: 12_247_451,
+            })
+        );
+    }
+
+    #[test]
+    fn deposit_amounts_lp_from_base_side() {
+        let mut amm = amm(AmmStatus::SwapOnly);
+        amm.state_data.need_take_pnl_coin = 0;
+        amm.state_data.need_take_pnl_pc = 0;
+        amm.lp_amount = 1_000_000;
+        // 1502 coin is taken for 1001 pc, the lp is minted from the pc side
+        let deposit =
+            deposit_amounts(&amm, &open_orders(), 3_000, 2_000, BaseSide::Pc, 1_001, 0).unwrap();
+        assert_eq!(deposit.max_coin_amount, 1_502);
+        assert_eq!(deposit.lp_amount, 500_500);
+        let deposit =
+            deposit_amounts(&amm, &open_orders(), 3_000, 2_000, BaseSide::Coin, 1_502, 0).unwrap();
+        assert_eq!(deposit.max_pc_amount, 1_002);
+        assert_eq!(deposit.lp_amount, 500_666);
+    }
+}
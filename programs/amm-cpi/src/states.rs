@@ -0,0 +1,545 @@
+//! Account layouts of the Raydium AMM program.
+use anchor_lang::{__private::bytemuck, error::ErrorCode, prelude::*};
+
+/// Status of an amm pool, stored as `u64` in `AmmInfo::status`
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmStatus {
+    /// The pool is not initialized
+    Uninitialized = 0,
+    /// All operations are allowed
+    Initialized = 1,
+    /// All operations are disabled
+    Disabled = 2,
+    /// Only withdraw is allowed
+    WithdrawOnly = 3,
+    /// Only deposit and withdraw are allowed
+    LiquidityOnly = 4,
+    /// Orderbook, deposit and withdraw are allowed
+    OrderBookOnly = 5,
+    /// Swap and withdraw are allowed
+    SwapOnly = 6,
+    /// The pool is waiting for `pool_open_time`, swap is allowed after it
+    WaitingTrade = 7,
+}
+
+impl AmmStatus {
+    /// Decode the status, returns `None` on an unknown value
+    pub fn from_u64(status: u64) -> Option<Self> {
+        match status {
+            0u64 => Some(AmmStatus::Uninitialized),
+            1u64 => Some(AmmStatus::Initialized),
+            2u64 => Some(AmmStatus::Disabled),
+            3u64 => Some(AmmStatus::WithdrawOnly),
+            4u64 => Some(AmmStatus::LiquidityOnly),
+            5u64 => Some(AmmStatus::OrderBookOnly),
+            6u64 => Some(AmmStatus::SwapOnly),
+            7u64 => Some(AmmStatus::WaitingTrade),
+            _ => None,
+        }
+    }
+
+    /// The raw value of the status
+    pub fn into_u64(self) -> u64 {
+        self as u64
+    }
+
+    /// Whether the amm places orders on the openbook market
+    pub fn orderbook_permission(&self) -> bool {
+        matches!(
+            self,
+            AmmStatus::Initialized | AmmStatus::OrderBookOnly | AmmStatus::WaitingTrade
+        )
+    }
+
+    /// Whether `Deposit` is allowed
+    pub fn deposit_permission(&self) -> bool {
+        matches!(
+            self,
+            AmmStatus::Initialized
+                | AmmStatus::LiquidityOnly
+                | AmmStatus::OrderBookOnly
+                | AmmStatus::WaitingTrade
+        )
+    }
+
+    /// Whether `Withdraw` is allowed
+    pub fn withdraw_permission(&self) -> bool {
+        !matches!(self, AmmStatus::Uninitialized | AmmStatus::Disabled)
+    }
+
+    /// Whether `SwapBaseIn` and `SwapBaseOut` are allowed,
+    /// `WaitingTrade` pools also require `pool_open_time` to be reached
+    pub fn swap_permission(&self) -> bool {
+        matches!(
+            self,
+            AmmStatus::Initialized | AmmStatus::SwapOnly | AmmStatus::WaitingTrade
+        )
+    }
+}
+
+/// State of the amm orderbook state machine, stored as `u64` in `AmmInfo::state`
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmState {
+    /// Invalid state
+    InvlidState = 0,
+    /// Nothing to do
+    IdleState = 1,
+    /// Cancelling all the orders
+    CancelAllOrdersState = 2,
+    /// Planning the orders
+    PlanOrdersState = 3,
+    /// Cancelling the orders
+    CancelOrderState = 4,
+    /// Placing the orders
+    PlaceOrdersState = 5,
+    /// Purging the orders
+    PurgeOrderState = 6,
+}
+
+impl AmmState {
+    /// Decode the state, returns `None` on an unknown value
+    pub fn from_u64(state: u64) -> Option<Self> {
+        match state {
+            0u64 => Some(AmmState::InvlidState),
+            1u64 => Some(AmmState::IdleState),
+            2u64 => Some(AmmState::CancelAllOrdersState),
+            3u64 => Some(AmmState::PlanOrdersState),
+            4u64 => Some(AmmState::CancelOrderState),
+            5u64 => Some(AmmState::PlaceOrdersState),
+            6u64 => Some(AmmState::PurgeOrderState),
+            _ => None,
+        }
+    }
+}
+
+/// Fee rates of an amm pool, each as a numerator and denominator pair
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug, PartialEq)]
+pub struct Fees {
+    /// numerator of the min_separate
+    pub min_separate_numerator: u64,
+    /// denominator of the min_separate
+    pub min_separate_denominator: u64,
+
+    /// numerator of the fee
+    pub trade_fee_numerator: u64,
+    /// denominator of the fee
+    /// and 'trade_fee_denominator' must be equal to 'min_separate_denominator'
+    pub trade_fee_denominator: u64,
+
+    /// numerator of the pnl
+    pub pnl_numerator: u64,
+    /// denominator of the pnl
+    pub pnl_denominator: u64,
+
+    /// numerator of the swap_fee
+    pub swap_fee_numerator: u64,
+    /// denominator of the swap_fee
+    pub swap_fee_denominator: u64,
+}
+
+impl Fees {
+    /// Size of the fees in `AmmInfo`
+    pub const LEN: usize = 8 * 8;
+}
+
+/// Accumulated pnl and swap statistics of an amm pool
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct StateData {
+    /// delay to take pnl coin
+    pub need_take_pnl_coin: u64,
+    /// delay to take pnl pc
+    pub need_take_pnl_pc: u64,
+    /// total pnl pc
+    pub total_pnl_pc: u64,
+    /// total pnl coin
+    pub total_pnl_coin: u64,
+    /// ido pool open time
+    pub pool_open_time: u64,
+    /// padding for future updates
+    pub padding: [u64; 2],
+    /// switch from orderbookonly to init
+    pub orderbook_to_init_time: u64,
+
+    /// swap coin in amount
+    pub swap_coin_in_amount: u128,
+    /// swap pc out amount
+    pub swap_pc_out_amount: u128,
+    /// charge pc as swap fee while swap pc to coin
+    pub swap_acc_pc_fee: u64,
+
+    /// swap pc in amount
+    pub swap_pc_in_amount: u128,
+    /// swap coin out amount
+    pub swap_coin_out_amount: u128,
+    /// charge coin as swap fee while swap coin to pc
+    pub swap_acc_coin_fee: u64,
+}
+
+impl StateData {
+    /// Size of the state data in `AmmInfo`
+    pub const LEN: usize = 8 * 8 + 16 * 2 + 8 + 16 * 2 + 8;
+}
+
+/// The `amm` account of a Raydium AMM pool
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct AmmInfo {
+    /// Initialized status, see `AmmStatus`
+    pub status: u64,
+    /// Nonce used in program address.
+    /// The program address is created deterministically with the nonce,
+    /// amm program id, and amm account pubkey.  This program address has
+    /// authority over the amm's token coin account, token pc account, and pool
+    /// token mint.
+    pub nonce: u64,
+    /// max order count
+    pub order_num: u64,
+    /// within this range, 5 => 5% range
+    pub depth: u64,
+    /// coin decimal
+    pub coin_decimals: u64,
+    /// pc decimal
+    pub pc_decimals: u64,
+    /// amm machine state, see `AmmState`
+    pub state: u64,
+    /// amm reset_flag
+    pub reset_flag: u64,
+    /// min size 1->0.000001
+    pub min_size: u64,
+    /// vol_max_cut_ratio numerator, sys_decimal_value as denominator
+    pub vol_max_cut_ratio: u64,
+    /// amount wave numerator, sys_decimal_value as denominator
+    pub amount_wave: u64,
+    /// coinLotSize 1 -> 0.000001
+    pub coin_lot_size: u64,
+    /// pcLotSize 1 -> 0.000001
+    pub pc_lot_size: u64,
+    /// min_cur_price: (2 * amm.order_num * amm.pc_lot_size) * max_price_multiplier
+    pub min_price_multiplier: u64,
+    /// max_cur_price: (2 * amm.order_num * amm.pc_lot_size) * max_price_multiplier
+    pub max_price_multiplier: u64,
+    /// system decimal value, used to normalize the value of coin and pc amount
+    pub sys_decimal_value: u64,
+    /// All fee information
+    pub fees: Fees,
+    /// Statistical data
+    pub state_data: StateData,
+    /// Coin vault
+    pub coin_vault: Pubkey,
+    /// Pc vault
+    pub pc_vault: Pubkey,
+    /// Coin vault mint
+    pub coin_vault_mint: Pubkey,
+    /// Pc vault mint
+    pub pc_vault_mint: Pubkey,
+    /// lp mint
+    pub lp_mint: Pubkey,
+    /// open_orders key
+    pub open_orders: Pubkey,
+    /// market key
+    pub market: Pubkey,
+    /// market program key
+    pub market_program: Pubkey,
+    /// target_orders key
+    pub target_orders: Pubkey,
+    /// padding
+    pub padding1: [u64; 8],
+    /// amm owner key
+    pub amm_owner: Pubkey,
+    /// pool lp amount
+    pub lp_amount: u64,
+    /// client order id
+    pub client_order_id: u64,
+    /// recent epoch
+    pub recent_epoch: u64,
+    /// padding
+    pub padding2: u64,
+}
+
+impl AmmInfo {
+    /// Size of the `amm` account
+    pub const LEN: usize = 16 * 8 + Fees::LEN + StateData::LEN + 9 * 32 + 8 * 8 + 32 + 4 * 8;
+
+    /// Decode the `amm` account, which must be owned by the amm program
+    pub fn load_checked(account: &AccountInfo) -> Result<Self> {
+        load_checked(account)
+    }
+
+    /// Decode the `status`, returns `None` on an unknown value
+    pub fn amm_status(&self) -> Option<AmmStatus> {
+        AmmStatus::from_u64(self.status)
+    }
+
+    /// Decode the `state`, returns `None` on an unknown value
+    pub fn amm_state(&self) -> Option<AmmState> {
+        AmmState::from_u64(self.state)
+    }
+
+    /// The fees of the pool
+    pub fn fees(&self) -> Fees {
+        self.fees
+    }
+
+    /// The statistical data of the pool
+    pub fn state_data(&self) -> StateData {
+        self.state_data
+    }
+
+    /// Pnl of coin and pc which is still in the vaults but no longer belongs to the pool
+    pub fn need_take_pnl(&self) -> (u64, u64) {
+        let state_data = self.state_data;
+        (state_data.need_take_pnl_coin, state_data.need_take_pnl_pc)
+    }
+
+    /// The time after which the pool can be swapped
+    pub fn pool_open_time(&self) -> u64 {
+        let state_data = self.state_data;
+        state_data.pool_open_time
+    }
+}
+
+/// An order planned by the amm
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct TargetOrder {
+    /// order price
+    pub price: u64,
+    /// order volume
+    pub vol: u64,
+}
+
+/// The `target_orders` account of a Raydium AMM pool
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Debug)]
+pub struct TargetOrders {
+    /// The owner amm
+    pub owner: [u64; 4],
+    /// planned buy orders
+    pub buy_orders: [TargetOrder; 50],
+    /// padding
+    pub padding1: [u64; 8],
+    /// target x
+    pub target_x: u128,
+    /// target y
+    pub target_y: u128,
+    /// plan x buy
+    pub plan_x_buy: u128,
+    /// plan y buy
+    pub plan_y_buy: u128,
+    /// plan x sell
+    pub plan_x_sell: u128,
+    /// plan y sell
+    pub plan_y_sell: u128,
+    /// placed x
+    pub placed_x: u128,
+    /// placed y
+    pub placed_y: u128,
+    /// calc pnl x
+    pub calc_pnl_x: u128,
+    /// calc pnl y
+    pub calc_pnl_y: u128,
+    /// planned sell orders
+    pub sell_orders: [TargetOrder; 50],
+    /// padding
+    pub padding2: [u64; 6],
+    /// client ids of the buy orders to be replaced
+    pub replace_buy_client_id: [u64; 10],
+    /// client ids of the sell orders to be replaced
+    pub replace_sell_client_id: [u64; 10],
+    /// last order numerator
+    pub last_order_numerator: u64,
+    /// last order denominator
+    pub last_order_denominator: u64,
+    /// plan orders cursor
+    pub plan_orders_cur: u64,
+    /// place orders cursor
+    pub place_orders_cur: u64,
+    /// valid buy order count
+    pub valid_buy_order_num: u64,
+    /// valid sell order count
+    pub valid_sell_order_num: u64,
+    /// padding
+    pub padding3: [u64; 10],
+    /// free slot bits
+    pub free_slot_bits: u128,
+}
+
+impl TargetOrders {
+    /// Size of the `target_orders` account
+    pub const LEN: usize =
+        4 * 8 + 16 * 50 + 8 * 8 + 16 * 10 + 16 * 50 + 8 * 6 + 8 * 10 * 2 + 8 * 6 + 8 * 10 + 16;
+
+    /// Decode the `target_orders` account, which must be owned by the amm program
+    pub fn load_checked(account: &AccountInfo) -> Result<Self> {
+        load_checked(account)
+    }
+
+    /// The amm which owns the target orders
+    pub fn owner(&self) -> Pubkey {
+        let owner = self.owner;
+        Pubkey::new_from_array(bytemuck::cast(owner))
+    }
+}
+
+fn load_checked<T: bytemuck::Pod>(account: &AccountInfo) -> Result<T> {
+    if *account.owner != crate::id() {
+        return Err(Error::from(ErrorCode::AccountOwnedByWrongProgram)
+            .with_pubkeys((*account.owner, crate::id())));
+    }
+    let data = account.try_borrow_data()?;
+    if data.len() != std::mem::size_of::<T>() {
+        return err!(ErrorCode::AccountDidNotDeserialize);
+    }
+    Ok(bytemuck::pod_read_unaligned(&data))
+}
+
+// Safe. Both layouts are packed without padding and all the fields are plain integers.
+unsafe impl bytemuck::Zeroable for AmmInfo {}
+unsafe impl bytemuck::Pod for AmmInfo {}
+unsafe impl bytemuck::Zeroable for TargetOrders {}
+unsafe impl bytemuck::Pod for TargetOrders {}
+
+const _: () = assert!(AmmInfo::LEN == std::mem::size_of::<AmmInfo>());
+const _: () = assert!(TargetOrders::LEN == std::mem::size_of::<TargetOrders>());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_key(data: &mut [u8], offset: usize, key: &Pubkey) {
+        data[offset..offset + 32].copy_from_slice(key.as_ref());
+    }
+
+    fn load<T>(
+        owner: &Pubkey,
+        mut data: Vec<u8>,
+        load_checked: impl Fn(&AccountInfo) -> Result<T>,
+    ) -> Result<T> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            owner,
+            false,
+            0,
+        );
+        load_checked(&account)
+    }
+
+    #[test]
+    fn account_sizes() {
+        assert_eq!(AmmInfo::LEN, 752);
+        assert_eq!(TargetOrders::LEN, 2208);
+    }
+
+    #[test]
+    fn amm_info_field_offsets() {
+        let keys: Vec<Pubkey> = (0..10).map(|_| Pubkey::new_unique()).collect();
+        let mut data = vec![0u8; AmmInfo::LEN];
+        write_u64(&mut data, 0, AmmStatus::SwapOnly as u64);
+        write_u64(&mut data, 8, 254);
+        write_u64(&mut data, 32, 9);
+        write_u64(&mut data, 40, 6);
+        write_u64(&mut data, 176, 25);
+        write_u64(&mut data, 184, 10_000);
+        write_u64(&mut data, 192, 1_000);
+        write_u64(&mut data, 200, 2_000);
+        write_u64(&mut data, 224, 1_700_000_000);
+        for (index, key) in keys.iter().take(9).enumerate() {
+            write_key(&mut data, 336 + 32 * index, key);
+        }
+        write_key(&mut data, 688, &keys[9]);
+        write_u64(&mut data, 720, 5_000_000);
+
+        let amm_info = load(&crate::id(), data, AmmInfo::load_checked).unwrap();
+        assert_eq!(amm_info.amm_status(), Some(AmmStatus::SwapOnly));
+        let (nonce, coin_decimals, pc_decimals) =
+            (amm_info.nonce, amm_info.coin_decimals, amm_info.pc_decimals);
+        assert_eq!((nonce, coin_decimals, pc_decimals), (254, 9, 6));
+        let fees = amm_info.fees();
+        let (swap_fee_numerator, swap_fee_denominator) =
+            (fees.swap_fee_numerator, fees.swap_fee_denominator);
+        assert_eq!((swap_fee_numerator, swap_fee_denominator), (25, 10_000));
+        assert_eq!(amm_info.need_take_pnl(), (1_000, 2_000));
+        assert_eq!(amm_info.pool_open_time(), 1_700_000_000);
+        let pool_keys = [
+            amm_info.coin_vault,
+            amm_info.pc_vault,
+            amm_info.coin_vault_mint,
+            amm_info.pc_vault_mint,
+            amm_info.lp_mint,
+            amm_info.open_orders,
+            amm_info.market,
+            amm_info.market_program,
+            amm_info.target_orders,
+            amm_info.amm_owner,
+        ];
+        assert_eq!(pool_keys.to_vec(), keys);
+        let lp_amount = amm_info.lp_amount;
+        assert_eq!(lp_amount, 5_000_000);
+    }
+
+    #[test]
+    fn amm_info_load_checked_rejects_invalid_accounts() {
+        assert_eq!(
+            load(
+                &Pubkey::new_unique(),
+                vec![0u8; AmmInfo::LEN],
+                AmmInfo::load_checked
+            )
+            .unwrap_err(),
+            ErrorCode::AccountOwnedByWrongProgram.into()
+        );
+        for len in [0, AmmInfo::LEN - 1, AmmInfo::LEN + 1, TargetOrders::LEN] {
+            assert_eq!(
+                load(&crate::id(), vec![0u8; len], AmmInfo::load_checked).unwrap_err(),
+                ErrorCode::AccountDidNotDeserialize.into()
+            );
+        }
+    }
+
+    #[test]
+    fn target_orders_load_checked() {
+        let amm = Pubkey::new_unique();
+        let mut data = vec![0u8; TargetOrders::LEN];
+        write_key(&mut data, 0, &amm);
+        let target_orders = load(&crate::id(), data, TargetOrders::load_checked).unwrap();
+        assert_eq!(target_orders.owner(), amm);
+
+        assert_eq!(
+            load(
+                &anchor_spl::token::ID,
+                vec![0u8; TargetOrders::LEN],
+                TargetOrders::load_checked
+            )
+            .unwrap_err(),
+            ErrorCode::AccountOwnedByWrongProgram.into()
+        );
+        assert_eq!(
+            load(
+                &crate::id(),
+                vec![0u8; AmmInfo::LEN],
+                TargetOrders::load_checked
+            )
+            .unwrap_err(),
+            ErrorCode::AccountDidNotDeserialize.into()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::states::*;
+use crate::{error::ErrorCode, states::*};
 use anchor_lang::{accounts::interface_account::InterfaceAccount, prelude::*};
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -529,6 +529,118 @@ pub struct Swap<'info> {
     pub observation_state: AccountLoader<'info, ObservationState>,
 }
 
+/// Accounts for a swap whose direction is given by [TradeDirection] rather than by the
+/// order of the accounts. Pool accounts are passed as token_0 / token_1, like in the pool state.
+#[derive(Accounts)]
+pub struct SwapByDirection<'info> {
+    /// CHECK: The user performing the swap, may be a PDA signing with signer seeds
+    pub payer: AccountInfo<'info>,
+
+    /// CHECK: pool vault and lp mint authority, checked by the cpmm program
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: The factory state to read protocol fees, must be `pool_state.amm_config`
+    pub amm_config: AccountInfo<'info>,
+
+    /// The program account of the pool in which the swap will be performed
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: The user token account for token_0
+    #[account(mut)]
+    pub token_0_account: AccountInfo<'info>,
+
+    /// CHECK: The user token account for token_1
+    #[account(mut)]
+    pub token_1_account: AccountInfo<'info>,
+
+    /// CHECK: The vault token account for token_0, must be `pool_state.token_0_vault`
+    #[account(mut)]
+    pub token_0_vault: AccountInfo<'info>,
+
+    /// CHECK: The vault token account for token_1, must be `pool_state.token_1_vault`
+    #[account(mut)]
+    pub token_1_vault: AccountInfo<'info>,
+
+    /// CHECK: SPL program for token_0 transfers, must be `pool_state.token_0_program`
+    pub token_0_program: AccountInfo<'info>,
+
+    /// CHECK: SPL program for token_1 transfers, must be `pool_state.token_1_program`
+    pub token_1_program: AccountInfo<'info>,
+
+    /// CHECK: The mint of token_0, must be `pool_state.token_0_mint`
+    pub token_0_mint: AccountInfo<'info>,
+
+    /// CHECK: The mint of token_1, must be `pool_state.token_1_mint`
+    pub token_1_mint: AccountInfo<'info>,
+
+    /// CHECK: The program account for the most recent oracle observation, must be `pool_state.observation_key`
+    #[account(mut)]
+    pub observation_state: AccountInfo<'info>,
+}
+
+impl<'info> SwapByDirection<'info> {
+    /// Check the pool accounts against the pool state and arrange them as the `Swap` accounts
+    /// for the given trade direction.
+    pub fn to_swap_accounts(
+        &self,
+        trade_direction: TradeDirection,
+    ) -> Result<crate::cpi::accounts::Swap<'info>> {
+        {
+            let pool_state = self.pool_state.load()?;
+            require_keys_eq!(self.amm_config.key(), pool_state.amm_config);
+            require_keys_eq!(self.observation_state.key(), pool_state.observation_key);
+            require_keys_eq!(
+                self.token_0_vault.key(),
+                pool_state.token_0_vault,
+                ErrorCode::InvalidVault
+            );
+            require_keys_eq!(
+                self.token_1_vault.key(),
+                pool_state.token_1_vault,
+                ErrorCode::InvalidVault
+            );
+            require_keys_eq!(self.token_0_mint.key(), pool_state.token_0_mint);
+            require_keys_eq!(self.token_1_mint.key(), pool_state.token_1_mint);
+            require_keys_eq!(self.token_0_program.key(), pool_state.token_0_program);
+            require_keys_eq!(self.token_1_program.key(), pool_state.token_1_program);
+        }
+
+        let token_0 = (
+            self.token_0_account.clone(),
+            self.token_0_vault.clone(),
+            self.token_0_program.clone(),
+            self.token_0_mint.clone(),
+        );
+        let token_1 = (
+            self.token_1_account.clone(),
+            self.token_1_vault.clone(),
+            self.token_1_program.clone(),
+            self.token_1_mint.clone(),
+        );
+        let (input, output) = match trade_direction {
+            TradeDirection::ZeroForOne => (token_0, token_1),
+            TradeDirection::OneForZero => (token_1, token_0),
+        };
+
+        Ok(crate::cpi::accounts::Swap {
+            payer: self.payer.clone(),
+            authority: self.authority.clone(),
+            amm_config: self.amm_config.clone(),
+            pool_state: self.pool_state.to_account_info(),
+            input_token_account: input.0,
+            output_token_account: output.0,
+            input_vault: input.1,
+            output_vault: output.1,
+            input_token_program: input.2,
+            output_token_program: output.2,
+            input_token_mint: input.3,
+            output_token_mint: output.3,
+            observation_state: self.observation_state.clone(),
+        })
+    }
+}
+
 #[derive(Accounts)]
 pub struct CollectCreatorFee<'info> {
     /// Only pool creator can collect fee
@@ -606,4 +718,140 @@ pub struct CollectCreatorFee<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     /// To create a new program account
     pub system_program: Program<'info, System>,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn new(key: Pubkey) -> Self {
+            Self {
+                key,
+                owner: Pubkey::default(),
+                lamports: 0,
+                data: Vec::new(),
+            }
+        }
+
+        fn pool_state(key: Pubkey, pool_state: &PoolState) -> Self {
+            let mut data = PoolState::DISCRIMINATOR.to_vec();
+            data.extend_from_slice(anchor_lang::__private::bytemuck::bytes_of(pool_state));
+            Self {
+                owner: crate::id(),
+                data,
+                ..Self::new(key)
+            }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                true,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    #[test]
+    fn to_swap_accounts() {
+        let pool_state = PoolState {
+            amm_config: Pubkey::new_unique(),
+            token_0_vault: Pubkey::new_unique(),
+            token_1_vault: Pubkey::new_unique(),
+            token_0_mint: Pubkey::new_unique(),
+            token_1_mint: Pubkey::new_unique(),
+            token_0_program: anchor_spl::token::ID,
+            token_1_program: anchor_spl::token_2022::ID,
+            observation_key: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let mut payer = TestAccount::new(Pubkey::new_unique());
+        let mut authority = TestAccount::new(Pubkey::new_unique());
+        let mut amm_config = TestAccount::new(pool_state.amm_config);
+        let mut pool = TestAccount::pool_state(Pubkey::new_unique(), &pool_state);
+        let mut token_0_account = TestAccount::new(Pubkey::new_unique());
+        let mut token_1_account = TestAccount::new(Pubkey::new_unique());
+        let mut token_0_vault = TestAccount::new(pool_state.token_0_vault);
+        let mut token_1_vault = TestAccount::new(pool_state.token_1_vault);
+        let mut token_0_program = TestAccount::new(pool_state.token_0_program);
+        let mut token_1_program = TestAccount::new(pool_state.token_1_program);
+        let mut token_0_mint = TestAccount::new(pool_state.token_0_mint);
+        let mut token_1_mint = TestAccount::new(pool_state.token_1_mint);
+        let mut observation_state = TestAccount::new(pool_state.observation_key);
+        let mut other_vault = TestAccount::new(Pubkey::new_unique());
+
+        let pool_info = pool.info();
+        let mut accounts = SwapByDirection {
+            payer: payer.info(),
+            authority: authority.info(),
+            amm_config: amm_config.info(),
+            pool_state: AccountLoader::try_from(&pool_info).unwrap(),
+            token_0_account: token_0_account.info(),
+            token_1_account: token_1_account.info(),
+            token_0_vault: token_0_vault.info(),
+            token_1_vault: token_1_vault.info(),
+            token_0_program: token_0_program.info(),
+            token_1_program: token_1_program.info(),
+            token_0_mint: token_0_mint.info(),
+            token_1_mint: token_1_mint.info(),
+            observation_state: observation_state.info(),
+        };
+        let keys = |swap: &crate::cpi::accounts::Swap| {
+            [
+                swap.input_token_account.key(),
+                swap.output_token_account.key(),
+                swap.input_vault.key(),
+                swap.output_vault.key(),
+                swap.input_token_program.key(),
+                swap.output_token_program.key(),
+                swap.input_token_mint.key(),
+                swap.output_token_mint.key(),
+            ]
+        };
+        let zero_for_one = [
+            accounts.token_0_account.key(),
+            accounts.token_1_account.key(),
+            pool_state.token_0_vault,
+            pool_state.token_1_vault,
+            pool_state.token_0_program,
+            pool_state.token_1_program,
+            pool_state.token_0_mint,
+            pool_state.token_1_mint,
+        ];
+
+        let swap = accounts
+            .to_swap_accounts(TradeDirection::ZeroForOne)
+            .unwrap();
+        assert_eq!(keys(&swap), zero_for_one);
+        assert_eq!(swap.pool_state.key(), pool_info.key());
+        assert_eq!(swap.observation_state.key(), pool_state.observation_key);
+
+        let swap = accounts
+            .to_swap_accounts(TradeDirection::OneForZero)
+            .unwrap();
+        let mut one_for_zero = zero_for_one;
+        for pair in one_for_zero.chunks_mut(2) {
+            pair.swap(0, 1);
+        }
+        assert_eq!(keys(&swap), one_for_zero);
+
+        accounts.token_1_vault = other_vault.info();
+        assert_eq!(
+            accounts.to_swap_accounts(TradeDirection::ZeroForOne).err(),
+            Some(ErrorCode::InvalidVault.into())
+        );
+    }
+}
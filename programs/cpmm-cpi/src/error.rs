@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+
+/// Errors that may be returned by the cpmm program.
+#[error_code]
+#[derive(PartialEq, Eq)]
+pub enum ErrorCode {
+    #[msg("Not approved")]
+    NotApproved,
+    /// The owner of the input isn't set to the program address generated by the program.
+    #[msg("Input account owner is not the program address")]
+    InvalidOwner,
+    /// The input token account is empty.
+    #[msg("Input token account empty")]
+    EmptySupply,
+    /// The input token is invalid for swap.
+    #[msg("InvalidInput")]
+    InvalidInput,
+    /// Address of the provided pool token mint is incorrect
+    #[msg("Address of the provided lp token mint is incorrect")]
+    IncorrectLpMint,
+    /// Exceeds desired slippage limit
+    #[msg("Exceeds desired slippage limit")]
+    ExceededSlippage,
+    /// Given pool token amount results in zero trading tokens
+    #[msg("Given pool token amount results in zero trading tokens")]
+    ZeroTradingTokens,
+    #[msg("Not support token_2022 mint extension")]
+    NotSupportMint,
+    #[msg("invaild vault")]
+    InvalidVault,
+    #[msg("Init lp amount is too less(Because 100 amount lp will be locked)")]
+    InitLpAmountTooLess,
+    #[msg("TransferFee calculate not match")]
+    TransferFeeCalculateNotMatch,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Insufficient vault")]
+    InsufficientVault,
+    #[msg("Invalid fee model")]
+    InvalidFeeModel,
+    #[msg("Fee is zero")]
+    NoFeeCollect,
+}
+
+impl ErrorCode {
+    const ALL: [ErrorCode; 15] = [
+        ErrorCode::NotApproved,
+        ErrorCode::InvalidOwner,
+        ErrorCode::EmptySupply,
+        ErrorCode::InvalidInput,
+        ErrorCode::IncorrectLpMint,
+        ErrorCode::ExceededSlippage,
+        ErrorCode::ZeroTradingTokens,
+        ErrorCode::NotSupportMint,
+        ErrorCode::InvalidVault,
+        ErrorCode::InitLpAmountTooLess,
+        ErrorCode::TransferFeeCalculateNotMatch,
+        ErrorCode::MathOverflow,
+        ErrorCode::InsufficientVault,
+        ErrorCode::InvalidFeeModel,
+        ErrorCode::NoFeeCollect,
+    ];
+
+    /// Look up the error from the raw custom error number returned by the program,
+    /// e.g. `6005` is `ExceededSlippage`
+    pub fn from_code(code: u32) -> Option<Self> {
+        let index = code.checked_sub(anchor_lang::error::ERROR_CODE_OFFSET)?;
+        Self::ALL.get(index as usize).copied()
+    }
+
+    /// Look up the error from a failed cpmm invoke, returns `None` if the error is not
+    /// a custom error of the cpmm program
+    pub fn from_program_error(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => Self::from_code(*code),
+            _ => None,
+        }
+    }
+
+    /// Look up the error from the result of a failed cpmm CPI
+    pub fn from_error(error: &Error) -> Option<Self> {
+        match error {
+            Error::AnchorError(error) => Self::from_code(error.error_code_number),
+            Error::ProgramError(error) => Self::from_program_error(&error.program_error),
+        }
+    }
+}
+
+impl TryFrom<u32> for ErrorCode {
+    type Error = ProgramError;
+
+    fn try_from(code: u32) -> std::result::Result<Self, Self::Error> {
+        Self::from_code(code).ok_or(ProgramError::Custom(code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_code_matches_error_numbers() {
+        for error in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_code(u32::from(error)), Some(error));
+        }
+        assert_eq!(ErrorCode::from_code(6000), Some(ErrorCode::NotApproved));
+        assert_eq!(
+            ErrorCode::from_code(6005),
+            Some(ErrorCode::ExceededSlippage)
+        );
+        assert_eq!(ErrorCode::from_code(6014), Some(ErrorCode::NoFeeCollect));
+        assert_eq!(ErrorCode::from_code(6015), None);
+        assert_eq!(ErrorCode::from_code(5999), None);
+        assert_eq!(ErrorCode::from_code(0), None);
+    }
+
+    #[test]
+    fn try_from_u32() {
+        assert_eq!(ErrorCode::try_from(6008), Ok(ErrorCode::InvalidVault));
+        assert_eq!(ErrorCode::try_from(6015), Err(ProgramError::Custom(6015)));
+    }
+
+    #[test]
+    fn from_program_error() {
+        assert_eq!(
+            ErrorCode::from_program_error(&ProgramError::Custom(6005)),
+            Some(ErrorCode::ExceededSlippage)
+        );
+        assert_eq!(
+            ErrorCode::from_program_error(&ProgramError::InvalidArgument),
+            None
+        );
+        assert_eq!(
+            ErrorCode::from_error(&error!(ErrorCode::MathOverflow)),
+            Some(ErrorCode::MathOverflow)
+        );
+        assert_eq!(
+            ErrorCode::from_error(&ProgramError::Custom(6012).into()),
+            Some(ErrorCode::InsufficientVault)
+        );
+        assert_eq!(
+            ErrorCode::from_error(&anchor_lang::error::ErrorCode::ConstraintAddress.into()),
+            None
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use anchor_lang::{event::EVENT_IX_TAG_LE, prelude::*, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Prefix of the log line written by `emit!`
+pub const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Emitted when deposit and withdraw
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LpChangeEvent {
+    pub pool_id: Pubkey,
+    pub lp_amount_before: u64,
+    /// pool vault sub trade fees
+    pub token_0_vault_before: u64,
+    /// pool vault sub trade fees
+    pub token_1_vault_before: u64,
+    /// calculate result without transfer fee
+    pub token_0_amount: u64,
+    /// calculate result without transfer fee
+    pub token_1_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+    // 0: deposit, 1: withdraw
+    pub change_type: u8,
+}
+
+/// Emitted when swap
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapEvent {
+    pub pool_id: Pubkey,
+    /// pool vault sub trade fees
+    pub input_vault_before: u64,
+    /// pool vault sub trade fees
+    pub output_vault_before: u64,
+    /// calculate result without transfer fee
+    pub input_amount: u64,
+    /// calculate result without transfer fee
+    pub output_amount: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+    pub base_input: bool,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub trade_fee: u64,
+    /// Amount of fee tokens going to creator
+    pub creator_fee: u64,
+    pub creator_fee_on_input: bool,
+}
+
+/// All events emitted by the cpmm program
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CpmmEvent {
+    LpChange(LpChangeEvent),
+    Swap(SwapEvent),
+}
+
+impl CpmmEvent {
+    /// Decode an event from its discriminator prefixed data,
+    /// returns `None` if the data is not a known cpmm event
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if let Some(mut rest) = data.strip_prefix(LpChangeEvent::DISCRIMINATOR) {
+            return LpChangeEvent::deserialize(&mut rest)
+                .ok()
+                .map(Self::LpChange);
+        }
+        if let Some(mut rest) = data.strip_prefix(SwapEvent::DISCRIMINATOR) {
+            return SwapEvent::deserialize(&mut rest).ok().map(Self::Swap);
+        }
+        None
+    }
+
+    /// Decode an event from a single `Program data: <base64>` log line
+    pub fn from_log(log: &str) -> Option<Self> {
+        let encoded = log.strip_prefix(PROGRAM_DATA_LOG_PREFIX)?;
+        let data = STANDARD.decode(encoded.trim()).ok()?;
+        Self::decode(&data)
+    }
+
+    /// Decode an event from the data of a self-CPI event instruction,
+    /// i.e. `EVENT_IX_TAG_LE` followed by the event discriminator and data
+    pub fn from_cpi_instruction_data(data: &[u8]) -> Option<Self> {
+        Self::decode(data.strip_prefix(EVENT_IX_TAG_LE)?)
+    }
+
+    /// Decode all events from the log messages of a transaction.
+    ///
+    /// Only `Program data:` lines written while the cpmm program is the
+    /// innermost invoked program are decoded, so events of other programs
+    /// sharing the same transaction are skipped.
+    pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<Self> {
+        parse_program_logs(&crate::id(), logs, Self::from_log)
+    }
+}
+
+/// Decode the log lines written by `program_id` from the log messages of a transaction.
+///
+/// The invocation stack is tracked from the `Program <id> invoke`, `success` and
+/// `failed` lines, and `decode` is called on every other line written while
+/// `program_id` is the innermost invoked program. Lines of other programs, including
+/// programs invoked by `program_id` or invoking it, are skipped.
+pub fn parse_program_logs<T, S: AsRef<str>>(
+    program_id: &Pubkey,
+    logs: &[S],
+    mut decode: impl FnMut(&str) -> Option<T>,
+) -> Vec<T> {
+    let program_id = program_id.to_string();
+    // Whether each frame of the invocation stack is `program_id`
+    let mut invoke_stack: Vec<bool> = Vec::new();
+    let mut decoded = Vec::new();
+    for log in logs {
+        let log = log.as_ref();
+        let mut parts = log.split_whitespace();
+        if let (Some("Program"), Some(id), Some(status)) =
+            (parts.next(), parts.next(), parts.next())
+        {
+            // `Program log:`, `Program data:` and so on are written by the program
+            if !id.ends_with(':') {
+                if status == "invoke" {
+                    invoke_stack.push(id == program_id);
+                } else if status == "success" || status.starts_with("failed") {
+                    invoke_stack.pop();
+                }
+                continue;
+            }
+        }
+        if invoke_stack.last() == Some(&true) {
+            decoded.extend(decode(log));
+        }
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+
+    const OTHER_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+    fn swap_event() -> SwapEvent {
+        SwapEvent {
+            pool_id: Pubkey::new_unique(),
+            input_vault_before: 1_000_000_000,
+            output_vault_before: 2_000_000_000,
+            input_amount: 1_000_000,
+            output_amount: 1_993_013,
+            input_transfer_fee: 0,
+            output_transfer_fee: 0,
+            base_input: true,
+            input_mint: Pubkey::new_unique(),
+            output_mint: Pubkey::new_unique(),
+            trade_fee: 2_500,
+            creator_fee: 0,
+            creator_fee_on_input: true,
+        }
+    }
+
+    fn lp_change_event() -> LpChangeEvent {
+        LpChangeEvent {
+            pool_id: Pubkey::new_unique(),
+            lp_amount_before: 1_414_213_562,
+            token_0_vault_before: 1_000_000_000,
+            token_1_vault_before: 2_000_000_000,
+            token_0_amount: 1_000_000,
+            token_1_amount: 2_000_000,
+            token_0_transfer_fee: 0,
+            token_1_transfer_fee: 0,
+            change_type: 0,
+        }
+    }
+
+    fn data_log(data: &[u8]) -> String {
+        format!("{}{}", PROGRAM_DATA_LOG_PREFIX, STANDARD.encode(data))
+    }
+
+    #[test]
+    fn from_log_decodes_both_events() {
+        let swap = swap_event();
+        let lp_change = lp_change_event();
+        assert_eq!(
+            CpmmEvent::from_log(&data_log(&swap.data())),
+            Some(CpmmEvent::Swap(swap))
+        );
+        assert_eq!(
+            CpmmEvent::from_log(&data_log(&lp_change.data())),
+            Some(CpmmEvent::LpChange(lp_change))
+        );
+    }
+
+    /// A 1 SOL to USDC `SwapEvent` encoded field by field from the layout of the
+    /// deployed program, independently of `SwapEvent` in this crate
+    const SWAP_EVENT_LOG: &str = "Program data: QMbN6CYIceLoJlH+Zgk6jwAkytUID3JksV1s9BtIjJXOnf+hdGV1mgAQpdToAAAAAFyy7CIAAAAAypo7AAAAABvW6AgAAAAAAAAAAAAAAAAAAAAAAAAAAAEGm4hX/quBhPtof2NGGMA12sQ53BrrO1WYoPAAAAAAAcb6evO+2606PWXzaqvJdDGxu+TC0vbg5HymAgNFL11hoCUmAAAAAAAgoQcAAAAAAAE=";
+
+    #[test]
+    fn from_log_decodes_program_layout() {
+        assert_eq!(
+            CpmmEvent::from_log(SWAP_EVENT_LOG),
+            Some(CpmmEvent::Swap(SwapEvent {
+                pool_id: pubkey!("GdDUe5DE6AVJ8dbduWtYHXDV8qq7FJy3km8vdWHiJr6V"),
+                input_vault_before: 1_000_000_000_000,
+                output_vault_before: 150_000_000_000,
+                input_amount: 1_000_000_000,
+                output_amount: 149_476_891,
+                input_transfer_fee: 0,
+                output_transfer_fee: 0,
+                base_input: true,
+                input_mint: pubkey!("So11111111111111111111111111111111111111112"),
+                output_mint: pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+                trade_fee: 2_500_000,
+                creator_fee: 500_000,
+                creator_fee_on_input: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn from_log_rejects_invalid_lines() {
+        let data = swap_event().data();
+        // Not a `Program data:` line
+        let log = format!("Program log: {}", STANDARD.encode(&data));
+        assert_eq!(CpmmEvent::from_log(&log), None);
+        // Not base64
+        assert_eq!(CpmmEvent::from_log("Program data: !!!"), None);
+        // Truncated payload
+        assert_eq!(
+            CpmmEvent::from_log(&data_log(&data[..data.len() - 1])),
+            None
+        );
+        // Unknown discriminator
+        let mut unknown = data.clone();
+        unknown[0] ^= 0xff;
+        assert_eq!(CpmmEvent::from_log(&data_log(&unknown)), None);
+    }
+
+    #[test]
+    fn from_cpi_instruction_data_requires_event_tag() {
+        let swap = swap_event();
+        let mut data = EVENT_IX_TAG_LE.to_vec();
+        data.extend(swap.data());
+        assert_eq!(
+            CpmmEvent::from_cpi_instruction_data(&data),
+            Some(CpmmEvent::Swap(swap.clone()))
+        );
+        assert_eq!(CpmmEvent::from_cpi_instruction_data(&swap.data()), None);
+        assert_eq!(
+            CpmmEvent::from_cpi_instruction_data(&data[..data.len() - 1]),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_logs_only_decodes_cpmm_frames() {
+        let program_id = crate::id().to_string();
+        let swap = swap_event();
+        let lp_change = lp_change_event();
+        let other_swap = swap_event();
+        let mut truncated = lp_change.data();
+        truncated.pop();
+        let mut unknown = swap.data();
+        unknown[..8].copy_from_slice(&[0; 8]);
+        let logs = vec![
+            format!("Program {} invoke [1]", OTHER_PROGRAM_ID),
+            "Program log: Instruction: Route".to_string(),
+            // Emitted by the router before the cpmm program is invoked
+            data_log(&other_swap.data()),
+            format!("Program {} invoke [2]", program_id),
+            "Program log: Instruction: SwapBaseInput".to_string(),
+            format!("Program {} invoke [3]", anchor_spl::token::ID),
+            "Program log: Instruction: TransferChecked".to_string(),
+            // Emitted by a program invoked by the cpmm program
+            data_log(&other_swap.data()),
+            format!(
+                "Program {} consumed 6147 of 180000 compute units",
+                anchor_spl::token::ID
+            ),
+            format!("Program {} success", anchor_spl::token::ID),
+            data_log(&swap.data()),
+            data_log(&truncated),
+            data_log(&unknown),
+            format!(
+                "Program {} consumed 30000 of 200000 compute units",
+                program_id
+            ),
+            format!("Program {} success", program_id),
+            // Emitted by the router after the cpmm program returned
+            data_log(&other_swap.data()),
+            format!("Program {} invoke [2]", program_id),
+            "Program log: Instruction: Deposit".to_string(),
+            data_log(&lp_change.data()),
+            format!("Program {} success", program_id),
+            format!("Program {} success", OTHER_PROGRAM_ID),
+        ];
+        assert_eq!(
+            CpmmEvent::parse_logs(&logs),
+            vec![CpmmEvent::Swap(swap), CpmmEvent::LpChange(lp_change)]
+        );
+    }
+
+    #[test]
+    fn parse_logs_pops_failed_frames() {
+        let program_id = crate::id().to_string();
+        let swap = swap_event();
+        let logs = vec![
+            format!("Program {} invoke [1]", OTHER_PROGRAM_ID),
+            format!("Program {} invoke [2]", program_id),
+            data_log(&swap.data()),
+            format!(
+                "Program {} failed: custom program error: 0x1775",
+                program_id
+            ),
+            data_log(&swap_event().data()),
+        ];
+        assert_eq!(CpmmEvent::parse_logs(&logs), vec![CpmmEvent::Swap(swap)]);
+    }
+}
@@ -0,0 +1,286 @@
+//! Fee and constant product math of the cpmm program, used to project the result of a swap.
+
+/// Denominator of all fee rates, rates are in hundredths of a bip (10^-6)
+pub const FEE_RATE_DENOMINATOR_VALUE: u64 = 1_000_000;
+
+fn ceil_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
+    token_amount
+        .checked_mul(fee_numerator)?
+        .checked_add(fee_denominator)?
+        .checked_sub(1)?
+        .checked_div(fee_denominator)
+}
+
+/// Ceiling division as the program's `CheckedCeilDiv` does it, a quotient below one is
+/// rounded half up
+fn checked_ceil_div(numerator: u128, denominator: u128) -> Option<u128> {
+    let quotient = numerator.checked_div(denominator)?;
+    if quotient == 0 {
+        return Some(if numerator.checked_mul(2)? >= denominator {
+            1
+        } else {
+            0
+        });
+    }
+    if numerator.checked_rem(denominator)? > 0 {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+fn floor_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
+    token_amount
+        .checked_mul(fee_numerator)?
+        .checked_div(fee_denominator)
+}
+
+/// Trading fee of an amount, rounded up
+pub fn trading_fee(amount: u128, trade_fee_rate: u64) -> Option<u128> {
+    ceil_div(
+        amount,
+        u128::from(trade_fee_rate),
+        u128::from(FEE_RATE_DENOMINATOR_VALUE),
+    )
+}
+
+/// Creator fee of an amount, rounded up
+pub fn creator_fee(amount: u128, creator_fee_rate: u64) -> Option<u128> {
+    ceil_div(
+        amount,
+        u128::from(creator_fee_rate),
+        u128::from(FEE_RATE_DENOMINATOR_VALUE),
+    )
+}
+
+/// Protocol share of the trading fee, rounded down
+pub fn protocol_fee(trade_fee: u128, protocol_fee_rate: u64) -> Option<u128> {
+    floor_div(
+        trade_fee,
+        u128::from(protocol_fee_rate),
+        u128::from(FEE_RATE_DENOMINATOR_VALUE),
+    )
+}
+
+/// Fund share of the trading fee, rounded down
+pub fn fund_fee(trade_fee: u128, fund_fee_rate: u64) -> Option<u128> {
+    floor_div(
+        trade_fee,
+        u128::from(fund_fee_rate),
+        u128::from(FEE_RATE_DENOMINATOR_VALUE),
+    )
+}
+
+/// Split the creator fee out of a fee charged at `trade_fee_rate + creator_fee_rate`,
+/// rounded down so that the trading fee keeps the remainder
+pub fn split_creator_fee(
+    total_fee: u128,
+    trade_fee_rate: u64,
+    creator_fee_rate: u64,
+) -> Option<u128> {
+    let total_fee_rate = trade_fee_rate.checked_add(creator_fee_rate)?;
+    if total_fee_rate == 0 {
+        return Some(0);
+    }
+    floor_div(
+        total_fee,
+        u128::from(creator_fee_rate),
+        u128::from(total_fee_rate),
+    )
+}
+
+/// The amount which is `post_fee_amount` after a fee at `fee_rate` is taken, rounded up
+pub fn calculate_pre_fee_amount(post_fee_amount: u128, fee_rate: u64) -> Option<u128> {
+    if fee_rate == 0 {
+        return Some(post_fee_amount);
+    }
+    let denominator = FEE_RATE_DENOMINATOR_VALUE.checked_sub(fee_rate)?;
+    if denominator == 0 {
+        return None;
+    }
+    ceil_div(
+        post_fee_amount,
+        u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        u128::from(denominator),
+    )
+}
+
+/// Output amount of a constant product swap without fees, rounded down
+pub fn swap_base_input_without_fees(
+    input_amount: u128,
+    input_vault_amount: u128,
+    output_vault_amount: u128,
+) -> Option<u128> {
+    floor_div(
+        output_vault_amount,
+        input_amount,
+        input_vault_amount.checked_add(input_amount)?,
+    )
+}
+
+/// Input amount of a constant product swap without fees, rounded up.
+/// An input below one is rounded half up, i.e. to zero when it is below one half.
+pub fn swap_base_output_without_fees(
+    output_amount: u128,
+    input_vault_amount: u128,
+    output_vault_amount: u128,
+) -> Option<u128> {
+    let denominator = output_vault_amount.checked_sub(output_amount)?;
+    if denominator == 0 {
+        return None;
+    }
+    checked_ceil_div(input_vault_amount.checked_mul(output_amount)?, denominator)
+}
+
+/// Fees and amounts of a swap
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapResult {
+    /// Amount of input token the user pays, before any transfer fee
+    pub input_amount: u128,
+    /// Amount of output token the user receives, before any transfer fee
+    pub output_amount: u128,
+    pub trade_fee: u128,
+    pub protocol_fee: u128,
+    pub fund_fee: u128,
+    pub creator_fee: u128,
+    /// Whether `creator_fee` is paid in the input token
+    pub is_creator_fee_on_input: bool,
+}
+
+/// Rates of the fees charged by a swap
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeRates {
+    pub trade_fee_rate: u64,
+    pub creator_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+}
+
+/// Project a swap with a fixed input amount.
+///
+/// `input_vault_amount` and `output_vault_amount` are the pool reserves without
+/// accrued protocol, fund and creator fees.
+pub fn swap_base_input(
+    input_amount: u128,
+    input_vault_amount: u128,
+    output_vault_amount: u128,
+    rates: FeeRates,
+    is_creator_fee_on_input: bool,
+) -> Option<SwapResult> {
+    let (trade_fee, creator_fee, input_amount_less_fees) = if is_creator_fee_on_input {
+        let total_fee = trading_fee(
+            input_amount,
+            rates.trade_fee_rate.checked_add(rates.creator_fee_rate)?,
+        )?;
+        let creator_fee =
+            split_creator_fee(total_fee, rates.trade_fee_rate, rates.creator_fee_rate)?;
+        (
+            total_fee.checked_sub(creator_fee)?,
+            creator_fee,
+            input_amount.checked_sub(total_fee)?,
+        )
+    } else {
+        let trade_fee = trading_fee(input_amount, rates.trade_fee_rate)?;
+        (trade_fee, 0, input_amount.checked_sub(trade_fee)?)
+    };
+
+    let output_amount_swapped = swap_base_input_without_fees(
+        input_amount_less_fees,
+        input_vault_amount,
+        output_vault_amount,
+    )?;
+    let (creator_fee, output_amount) = if is_creator_fee_on_input {
+        (creator_fee, output_amount_swapped)
+    } else {
+        let creator_fee = self::creator_fee(output_amount_swapped, rates.creator_fee_rate)?;
+        (creator_fee, output_amount_swapped.checked_sub(creator_fee)?)
+    };
+
+    Some(SwapResult {
+        input_amount,
+        output_amount,
+        trade_fee,
+        protocol_fee: protocol_fee(trade_fee, rates.protocol_fee_rate)?,
+        fund_fee: fund_fee(trade_fee, rates.fund_fee_rate)?,
+        creator_fee,
+        is_creator_fee_on_input,
+    })
+}
+
+/// Project a swap with a fixed output amount.
+///
+/// `input_vault_amount` and `output_vault_amount` are the pool reserves without
+/// accrued protocol, fund and creator fees.
+pub fn swap_base_output(
+    output_amount: u128,
+    input_vault_amount: u128,
+    output_vault_amount: u128,
+    rates: FeeRates,
+    is_creator_fee_on_input: bool,
+) -> Option<SwapResult> {
+    let (trade_fee, creator_fee, input_amount) = if is_creator_fee_on_input {
+        let input_amount_less_fees =
+            swap_base_output_without_fees(output_amount, input_vault_amount, output_vault_amount)?;
+        let input_amount = calculate_pre_fee_amount(
+            input_amount_less_fees,
+            rates.trade_fee_rate.checked_add(rates.creator_fee_rate)?,
+        )?;
+        let total_fee = input_amount.checked_sub(input_amount_less_fees)?;
+        let creator_fee =
+            split_creator_fee(total_fee, rates.trade_fee_rate, rates.creator_fee_rate)?;
+        (
+            total_fee.checked_sub(creator_fee)?,
+            creator_fee,
+            input_amount,
+        )
+    } else {
+        let output_amount_swapped =
+            calculate_pre_fee_amount(output_amount, rates.creator_fee_rate)?;
+        let creator_fee = output_amount_swapped.checked_sub(output_amount)?;
+        let input_amount_less_fees = swap_base_output_without_fees(
+            output_amount_swapped,
+            input_vault_amount,
+            output_vault_amount,
+        )?;
+        let input_amount = calculate_pre_fee_amount(input_amount_less_fees, rates.trade_fee_rate)?;
+        let trade_fee = input_amount.checked_sub(input_amount_less_fees)?;
+        (trade_fee, creator_fee, input_amount)
+    };
+
+    Some(SwapResult {
+        input_amount,
+        output_amount,
+        trade_fee,
+        protocol_fee: protocol_fee(trade_fee, rates.protocol_fee_rate)?,
+        fund_fee: fund_fee(trade_fee, rates.fund_fee_rate)?,
+        creator_fee,
+        is_creator_fee_on_input,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_base_output_without_fees_rounds_like_checked_ceil_div() {
+        // 10^15 / (2 * 10^9 - 10^6) = 500250.12..., rounded up
+        assert_eq!(
+            swap_base_output_without_fees(1_000_000, 1_000_000_000, 2_000_000_000),
+            Some(500_251)
+        );
+        // Exact quotient
+        assert_eq!(swap_base_output_without_fees(1, 1_000, 1_001), Some(1));
+        // A quotient below one is rounded half up
+        assert_eq!(
+            swap_base_output_without_fees(1, 500_000, 1_000_001),
+            Some(1)
+        );
+        assert_eq!(
+            swap_base_output_without_fees(1, 499_999, 1_000_001),
+            Some(0)
+        );
+        // The whole output vault can not be bought
+        assert_eq!(swap_base_output_without_fees(1_000, 1_000, 1_000), None);
+    }
+}
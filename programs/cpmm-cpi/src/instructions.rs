@@ -0,0 +1,59 @@
+//! Swap invokers which resolve the input and output accounts from the pool state.
+
+use crate::{context::SwapByDirection, states::*};
+use anchor_lang::prelude::*;
+
+fn to_swap_context<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, SwapByDirection<'info>>,
+    trade_direction: TradeDirection,
+) -> Result<CpiContext<'a, 'b, 'c, 'info, crate::cpi::accounts::Swap<'info>>> {
+    let accounts = ctx.accounts.to_swap_accounts(trade_direction)?;
+    Ok(
+        CpiContext::new_with_signer(ctx.program, accounts, ctx.signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts),
+    )
+}
+
+/// Invokes `swap_base_input` with the input and output accounts selected by `trade_direction`.
+///
+/// # Arguments
+///
+/// * `ctx`- The context of accounts
+/// * `trade_direction` - ZeroForOne: input token_0, output token_1; OneForZero: input token_1, output token_0
+/// * `amount_in` -  input amount to transfer, output to DESTINATION is based on the exchange rate
+/// * `minimum_amount_out` -  Minimum amount of output token, prevents excessive slippage
+///
+pub fn swap_base_input<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, SwapByDirection<'info>>,
+    trade_direction: TradeDirection,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    crate::cpi::swap_base_input(
+        to_swap_context(ctx, trade_direction)?,
+        amount_in,
+        minimum_amount_out,
+    )
+}
+
+/// Invokes `swap_base_output` with the input and output accounts selected by `trade_direction`.
+///
+/// # Arguments
+///
+/// * `ctx`- The context of accounts
+/// * `trade_direction` - ZeroForOne: input token_0, output token_1; OneForZero: input token_1, output token_0
+/// * `max_amount_in` -  input amount prevents excessive slippage
+/// * `amount_out` -  amount of output token
+///
+pub fn swap_base_output<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, SwapByDirection<'info>>,
+    trade_direction: TradeDirection,
+    max_amount_in: u64,
+    amount_out: u64,
+) -> Result<()> {
+    crate::cpi::swap_base_output(
+        to_swap_context(ctx, trade_direction)?,
+        max_amount_in,
+        amount_out,
+    )
+}
@@ -1,9 +1,16 @@
 #![allow(unused)]
 
 pub mod context;
+pub mod error;
+pub mod events;
+pub mod fees;
+pub mod instructions;
 pub mod states;
+pub mod utils;
 pub use context::*;
+pub use events::*;
 pub use states::*;
+pub use utils::*;
 
 use anchor_lang::prelude::*;
 
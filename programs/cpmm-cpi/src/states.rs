@@ -1,3 +1,4 @@
+use crate::{error::ErrorCode, fees};
 use anchor_lang::prelude::*;
 /// Seed to derive account address and signature
 pub const POOL_SEED: &str = "pool";
@@ -64,6 +65,25 @@ pub enum CreatorFeeOn {
     OnlyToken1,
 }
 
+impl CreatorFeeOn {
+    pub fn from_u8(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(CreatorFeeOn::BothToken),
+            1 => Ok(CreatorFeeOn::OnlyToken0),
+            2 => Ok(CreatorFeeOn::OnlyToken1),
+            _ => err!(ErrorCode::InvalidFeeModel),
+        }
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            CreatorFeeOn::BothToken => 0,
+            CreatorFeeOn::OnlyToken0 => 1,
+            CreatorFeeOn::OnlyToken1 => 2,
+        }
+    }
+}
+
 /// The direction of a trade, since curves can be specialized to treat each
 /// token differently (by adding offsets or weights)
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -151,6 +171,119 @@ pub struct PoolState {
 }
 impl PoolState {
     pub const LEN: usize = 8 + 10 * 32 + 1 * 5 + 8 * 7 + 1 * 2 + 6 * 1 + 2 * 8 + 8 * 28;
+
+    /// Decode `creator_fee_on`, fails with `InvalidFeeModel` on an unknown value
+    pub fn get_creator_fee_on(&self) -> Result<CreatorFeeOn> {
+        CreatorFeeOn::from_u8(self.creator_fee_on)
+    }
+
+    /// Whether the creator fee of a trade in `trade_direction` is taken from the input token
+    pub fn is_creator_fee_on_input(&self, trade_direction: TradeDirection) -> Result<bool> {
+        Ok(match self.get_creator_fee_on()? {
+            CreatorFeeOn::BothToken => true,
+            CreatorFeeOn::OnlyToken0 => trade_direction == TradeDirection::ZeroForOne,
+            CreatorFeeOn::OnlyToken1 => trade_direction == TradeDirection::OneForZero,
+        })
+    }
+
+    /// The creator fee rate charged by the pool, zero if the creator fee is not enabled
+    pub fn creator_fee_rate(&self, amm_config: &AmmConfig) -> u64 {
+        if self.enable_creator_fee {
+            amm_config.creator_fee_rate
+        } else {
+            0
+        }
+    }
+
+    /// Creator fees of token_0 and token_1 that can be collected by `collect_creator_fee`
+    pub fn claimable_creator_fees(&self) -> (u64, u64) {
+        (self.creator_fees_token_0, self.creator_fees_token_1)
+    }
+
+    /// Pool reserves of token_0 and token_1 without accrued protocol, fund and creator fees
+    ///
+    /// # Arguments
+    ///
+    /// * `vault_0` - The amount of `token_0_vault`
+    /// * `vault_1` - The amount of `token_1_vault`
+    ///
+    pub fn vault_amount_without_fee(&self, vault_0: u64, vault_1: u64) -> Result<(u64, u64)> {
+        let fees_token_0 = self
+            .protocol_fees_token_0
+            .checked_add(self.fund_fees_token_0)
+            .and_then(|fees| fees.checked_add(self.creator_fees_token_0))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let fees_token_1 = self
+            .protocol_fees_token_1
+            .checked_add(self.fund_fees_token_1)
+            .and_then(|fees| fees.checked_add(self.creator_fees_token_1))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok((
+            vault_0
+                .checked_sub(fees_token_0)
+                .ok_or(ErrorCode::InsufficientVault)?,
+            vault_1
+                .checked_sub(fees_token_1)
+                .ok_or(ErrorCode::InsufficientVault)?,
+        ))
+    }
+
+    /// Project the creator fee accrued by a hypothetical swap, returns the fee amount and
+    /// whether it is paid in the input token.
+    ///
+    /// Transfer fees are not considered, amounts are what the pool vaults receive and send.
+    ///
+    /// # Arguments
+    ///
+    /// * `amm_config` - The config of the pool
+    /// * `trade_direction` - The direction of the swap
+    /// * `amount` - The input amount if `base_input`, otherwise the output amount
+    /// * `base_input` - Whether the swap is `swap_base_input` or `swap_base_output`
+    /// * `vault_0` - The amount of `token_0_vault`
+    /// * `vault_1` - The amount of `token_1_vault`
+    ///
+    pub fn projected_creator_fee(
+        &self,
+        amm_config: &AmmConfig,
+        trade_direction: TradeDirection,
+        amount: u64,
+        base_input: bool,
+        vault_0: u64,
+        vault_1: u64,
+    ) -> Result<(u64, bool)> {
+        let (vault_0, vault_1) = self.vault_amount_without_fee(vault_0, vault_1)?;
+        let (input_vault_amount, output_vault_amount) = match trade_direction {
+            TradeDirection::ZeroForOne => (vault_0, vault_1),
+            TradeDirection::OneForZero => (vault_1, vault_0),
+        };
+        let rates = fees::FeeRates {
+            trade_fee_rate: amm_config.trade_fee_rate,
+            creator_fee_rate: self.creator_fee_rate(amm_config),
+            protocol_fee_rate: amm_config.protocol_fee_rate,
+            fund_fee_rate: amm_config.fund_fee_rate,
+        };
+        let is_creator_fee_on_input = self.is_creator_fee_on_input(trade_direction)?;
+        let swap = if base_input {
+            fees::swap_base_input(
+                u128::from(amount),
+                u128::from(input_vault_amount),
+                u128::from(output_vault_amount),
+                rates,
+                is_creator_fee_on_input,
+            )
+        } else {
+            fees::swap_base_output(
+                u128::from(amount),
+                u128::from(input_vault_amount),
+                u128::from(output_vault_amount),
+                rates,
+                is_creator_fee_on_input,
+            )
+        }
+        .ok_or(ErrorCode::ZeroTradingTokens)?;
+        let creator_fee = u64::try_from(swap.creator_fee).map_err(|_| ErrorCode::MathOverflow)?;
+        Ok((creator_fee, is_creator_fee_on_input))
+    }
 }
 
 /// The element of observations in ObservationState
@@ -196,3 +329,184 @@ pub struct Permission {
     /// padding
     pub padding: [u64; 30],
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amm_config() -> AmmConfig {
+        AmmConfig {
+            trade_fee_rate: 2_500,
+            protocol_fee_rate: 120_000,
+            fund_fee_rate: 40_000,
+            creator_fee_rate: 1_000,
+            ..Default::default()
+        }
+    }
+
+    fn pool_state(creator_fee_on: CreatorFeeOn) -> PoolState {
+        PoolState {
+            protocol_fees_token_0: 100,
+            protocol_fees_token_1: 200,
+            fund_fees_token_0: 50,
+            fund_fees_token_1: 60,
+            creator_fee_on: creator_fee_on.to_u8(),
+            enable_creator_fee: true,
+            creator_fees_token_0: 30,
+            creator_fees_token_1: 40,
+            ..Default::default()
+        }
+    }
+
+    // Vault balances of 10^9 token_0 and 2 * 10^9 token_1 once the accrued fees are deducted
+    const VAULT_0: u64 = 1_000_000_180;
+    const VAULT_1: u64 = 2_000_000_300;
+
+    #[test]
+    fn claimable_creator_fees() {
+        for creator_fee_on in [
+            CreatorFeeOn::BothToken,
+            CreatorFeeOn::OnlyToken0,
+            CreatorFeeOn::OnlyToken1,
+        ] {
+            assert_eq!(
+                pool_state(creator_fee_on).claimable_creator_fees(),
+                (30, 40)
+            );
+        }
+    }
+
+    #[test]
+    fn vault_amount_without_fee() {
+        for creator_fee_on in [CreatorFeeOn::OnlyToken0, CreatorFeeOn::OnlyToken1] {
+            let pool_state = pool_state(creator_fee_on);
+            assert_eq!(
+                pool_state
+                    .vault_amount_without_fee(VAULT_0, VAULT_1)
+                    .unwrap(),
+                (1_000_000_000, 2_000_000_000)
+            );
+            assert_eq!(
+                pool_state.vault_amount_without_fee(180, 300).unwrap(),
+                (0, 0)
+            );
+            assert!(pool_state.vault_amount_without_fee(179, 300).is_err());
+            assert!(pool_state.vault_amount_without_fee(180, 299).is_err());
+        }
+    }
+
+    #[test]
+    fn projected_creator_fee_on_input() {
+        let amm_config = amm_config();
+        for creator_fee_on in [CreatorFeeOn::BothToken, CreatorFeeOn::OnlyToken0] {
+            let pool_state = pool_state(creator_fee_on);
+            // Fee of 3500 at the total rate, 1000 of it to the creator
+            assert_eq!(
+                pool_state
+                    .projected_creator_fee(
+                        &amm_config,
+                        TradeDirection::ZeroForOne,
+                        1_000_000,
+                        true,
+                        VAULT_0,
+                        VAULT_1,
+                    )
+                    .unwrap(),
+                (1_000, true)
+            );
+            // 500251 in without fees, 502009 in with a fee of 1758
+            assert_eq!(
+                pool_state
+                    .projected_creator_fee(
+                        &amm_config,
+                        TradeDirection::ZeroForOne,
+                        1_000_000,
+                        false,
+                        VAULT_0,
+                        VAULT_1,
+                    )
+                    .unwrap(),
+                (502, true)
+            );
+        }
+    }
+
+    #[test]
+    fn projected_creator_fee_on_output() {
+        let amm_config = amm_config();
+        let pool_state = pool_state(CreatorFeeOn::OnlyToken1);
+        // 1993011 out before the creator fee
+        assert_eq!(
+            pool_state
+                .projected_creator_fee(
+                    &amm_config,
+                    TradeDirection::ZeroForOne,
+                    1_000_000,
+                    true,
+                    VAULT_0,
+                    VAULT_1,
+                )
+                .unwrap(),
+            (1_994, false)
+        );
+        // 1001002 out before the creator fee
+        assert_eq!(
+            pool_state
+                .projected_creator_fee(
+                    &amm_config,
+                    TradeDirection::ZeroForOne,
+                    1_000_000,
+                    false,
+                    VAULT_0,
+                    VAULT_1,
+                )
+                .unwrap(),
+            (1_002, false)
+        );
+        // The other direction pays the creator fee on the input token 1
+        assert!(
+            pool_state
+                .projected_creator_fee(
+                    &amm_config,
+                    TradeDirection::OneForZero,
+                    1_000_000,
+                    true,
+                    VAULT_0,
+                    VAULT_1,
+                )
+                .unwrap()
+                .1
+        );
+    }
+
+    #[test]
+    fn projected_creator_fee_disabled() {
+        let amm_config = amm_config();
+        let mut pool_state = pool_state(CreatorFeeOn::BothToken);
+        pool_state.enable_creator_fee = false;
+        assert_eq!(
+            pool_state
+                .projected_creator_fee(
+                    &amm_config,
+                    TradeDirection::ZeroForOne,
+                    1_000_000,
+                    true,
+                    VAULT_0,
+                    VAULT_1,
+                )
+                .unwrap(),
+            (0, true)
+        );
+        pool_state.creator_fee_on = 3;
+        assert!(pool_state
+            .projected_creator_fee(
+                &amm_config,
+                TradeDirection::ZeroForOne,
+                1_000_000,
+                true,
+                VAULT_0,
+                VAULT_1,
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    },
+    token_interface::Mint,
+};
+
+/// Token-2022 mints accepted by the cpmm program regardless of their extensions
+pub const MINT_WHITELIST: [Pubkey; 4] = [
+    pubkey!("HVbpJAQGNpkgBaYBZQBR1t7yFdvaYVp2vCQQfKKEN4tM"),
+    pubkey!("Crn4x1Y2HUKko7ox2EZMT6N2t2ZyH7eKtwpBBVnhEUsV"),
+    pubkey!("FrBfWJ4qE5sCzKm3k3JaAtqZcXUh4LvJygDeketsrsH4"),
+    pubkey!("2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo"),
+];
+
+/// Token-2022 mint extensions supported by the cpmm program
+pub const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 4] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::InterestBearingConfig,
+];
+
+/// Check whether the mint can be used to create a cpmm pool, the same way the program does.
+///
+/// Spl token mints and whitelisted mints are always supported, token-2022 mints are supported
+/// only if all of their extensions are in `SUPPORTED_MINT_EXTENSIONS`.
+///
+/// # Arguments
+///
+/// * `mint_account` - The token_0 or token_1 mint of the pool to be created
+///
+pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool> {
+    let mint_info = mint_account.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(true);
+    }
+    if MINT_WHITELIST.contains(&mint_account.key()) {
+        return Ok(true);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let extensions = mint.get_extension_types()?;
+    Ok(extensions
+        .iter()
+        .all(|extension| SUPPORTED_MINT_EXTENSIONS.contains(extension)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::{
+        token::spl_token,
+        token_2022::spl_token_2022::{
+            extension::{
+                non_transferable::NonTransferable, transfer_fee::TransferFeeConfig,
+                BaseStateWithExtensionsMut, StateWithExtensionsMut,
+            },
+            solana_program::program_pack::Pack,
+        },
+    };
+
+    fn spl_token_mint_data() -> Vec<u8> {
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+
+    fn token_2022_mint_data(extensions: &[ExtensionType]) -> Vec<u8> {
+        let len =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions)
+                .unwrap();
+        let mut data = vec![0u8; len];
+        let mut mint =
+            StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+                .unwrap();
+        for extension in extensions {
+            match extension {
+                ExtensionType::TransferFeeConfig => {
+                    mint.init_extension::<TransferFeeConfig>(true).unwrap();
+                }
+                ExtensionType::NonTransferable => {
+                    mint.init_extension::<NonTransferable>(true).unwrap();
+                }
+                _ => unreachable!(),
+            }
+        }
+        mint.base = spl_token_2022::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    fn is_supported(key: Pubkey, owner: Pubkey, mut data: Vec<u8>) -> bool {
+        let mut lamports = 0;
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        let mint = InterfaceAccount::<Mint>::try_from(&account_info).unwrap();
+        is_supported_mint(&mint).unwrap()
+    }
+
+    #[test]
+    fn spl_token_mints_are_supported() {
+        assert!(is_supported(
+            Pubkey::new_unique(),
+            Token::id(),
+            spl_token_mint_data()
+        ));
+    }
+
+    #[test]
+    fn token_2022_mints_need_supported_extensions() {
+        let token_2022 = spl_token_2022::id();
+        assert!(is_supported(
+            Pubkey::new_unique(),
+            token_2022,
+            token_2022_mint_data(&[])
+        ));
+        assert!(is_supported(
+            Pubkey::new_unique(),
+            token_2022,
+            token_2022_mint_data(&[ExtensionType::TransferFeeConfig])
+        ));
+        assert!(!is_supported(
+            Pubkey::new_unique(),
+            token_2022,
+            token_2022_mint_data(&[
+                ExtensionType::TransferFeeConfig,
+                ExtensionType::NonTransferable
+            ])
+        ));
+        // Whitelisted mints skip the extension check
+        assert!(is_supported(
+            MINT_WHITELIST[0],
+            token_2022,
+            token_2022_mint_data(&[ExtensionType::NonTransferable])
+        ));
+    }
+}
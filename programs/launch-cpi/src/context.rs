@@ -18,6 +18,29 @@ pub struct TransferFeeExtensionParams {
     pub maximum_fee: u64,
 }
 
+impl TransferFeeExtensionParams {
+    /// The maximum value of `transfer_fee_basis_points`
+    pub const MAX_TRANSFER_FEE_BASIS_POINTS: u16 = 500;
+
+    /// Creates the transfer fee params of a base token with the given `supply`
+    pub fn new(transfer_fee_basis_points: u16, maximum_fee: u64, supply: u64) -> Result<Self> {
+        require_gte!(
+            Self::MAX_TRANSFER_FEE_BASIS_POINTS,
+            transfer_fee_basis_points
+        );
+        let supply_fee = u128::from(supply) * u128::from(transfer_fee_basis_points) / 10000;
+        require_gt!(u128::from(maximum_fee), supply_fee);
+        Ok(Self {
+            transfer_fee_basis_points,
+            maximum_fee,
+        })
+    }
+
+    pub fn transfer_fee_basis_points(&self) -> u16 {
+        self.transfer_fee_basis_points
+    }
+}
+
 /// Accounts required for initializing a new trading pool
 #[event_cpi]
 #[derive(Accounts)]
@@ -628,6 +651,312 @@ pub struct ClaimVestedToken<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+/// Accounts required for migrating a pool to the AMM after the fundraising ends
+///
+/// The migration is not permissionless, the launchpad program only accepts
+/// `GlobalConfig.migrate_to_amm_wallet` as the payer,
+/// so third party keepers can't migrate the pools of a global config
+#[derive(Accounts)]
+pub struct MigrateToAmm<'info> {
+    /// Only migrate_to_amm_wallet can migrate to amm pool, the same check as the launchpad program
+    #[account(
+        mut,
+        address = global_config.migrate_to_amm_wallet
+    )]
+    pub payer: Signer<'info>,
+
+    /// The mint of the base token
+    #[account(
+        address = pool_state.base_mint
+    )]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of the quote token
+    #[account(
+        address = pool_state.quote_mint
+    )]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: OpenBook program
+    #[account(
+        address = crate::openbook_program::ID
+    )]
+    pub openbook_program: UncheckedAccount<'info>,
+
+    /// CHECK: The OpenBook market, initialized by the instruction
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+
+    /// CHECK: The market request queue, initialized by the instruction
+    #[account(mut)]
+    pub request_queue: UncheckedAccount<'info>,
+
+    /// CHECK: The market event queue, initialized by the instruction
+    #[account(mut)]
+    pub event_queue: UncheckedAccount<'info>,
+
+    /// CHECK: The market bids, initialized by the instruction
+    #[account(mut)]
+    pub bids: UncheckedAccount<'info>,
+
+    /// CHECK: The market asks, initialized by the instruction
+    #[account(mut)]
+    pub asks: UncheckedAccount<'info>,
+
+    /// CHECK: The market vault signer, derived from the market and `market_vault_signer_nonce`
+    pub market_vault_signer: UncheckedAccount<'info>,
+
+    /// CHECK: The market base vault, checked by the OpenBook program
+    #[account(mut)]
+    pub market_base_vault: UncheckedAccount<'info>,
+
+    /// CHECK: The market quote vault, checked by the OpenBook program
+    #[account(mut)]
+    pub market_quote_vault: UncheckedAccount<'info>,
+
+    /// CHECK: AMM program
+    #[account(
+        address = crate::amm_program::ID
+    )]
+    pub amm_program: UncheckedAccount<'info>,
+
+    /// CHECK: The amm account, initialized by the AMM program
+    #[account(mut)]
+    pub amm_pool: UncheckedAccount<'info>,
+
+    /// CHECK: The amm authority, checked by the AMM program
+    pub amm_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The amm open orders, initialized by the AMM program
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+
+    /// CHECK: The amm lp mint, initialized by the AMM program
+    #[account(mut)]
+    pub amm_lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: The amm base vault, initialized by the AMM program
+    #[account(mut)]
+    pub amm_base_vault: UncheckedAccount<'info>,
+
+    /// CHECK: The amm quote vault, initialized by the AMM program
+    #[account(mut)]
+    pub amm_quote_vault: UncheckedAccount<'info>,
+
+    /// CHECK: The amm target orders, initialized by the AMM program
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+
+    /// CHECK: The config account of the AMM program
+    pub amm_config: UncheckedAccount<'info>,
+
+    /// CHECK: The pool creation fee receiver of the AMM program
+    #[account(mut)]
+    pub amm_create_fee_destination: UncheckedAccount<'info>,
+
+    /// PDA that acts as the authority for pool vault operations
+    /// Generated using AUTH_SEED
+    /// CHECK: This is a PDA, safety checks performed in seeds constraint
+    #[account(
+        mut,
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Account that stores the pool's state and parameters
+    /// PDA generated using POOL_SEED and both token mints
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    /// Global configuration account containing the migrate wallets
+    #[account(
+        address = pool_state.global_config
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// The pool's vault for base tokens
+    #[account(
+        mut,
+        address = pool_state.base_vault
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool's vault for quote tokens
+    #[account(
+        mut,
+        address = pool_state.quote_vault
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The lp token account of the authority, created by the AMM program
+    #[account(mut)]
+    pub pool_lp_token: UncheckedAccount<'info>,
+
+    /// SPL Token program for the base and quote tokens
+    /// Must be the standard Token program
+    pub spl_token_program: Program<'info, Token>,
+
+    /// Required for associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Required for account creation
+    pub system_program: Program<'info, System>,
+
+    /// Required for rent exempt calculations
+    pub rent_program: Sysvar<'info, Rent>,
+}
+
+/// Accounts required for migrating a pool to the CP-Swap after the fundraising ends
+///
+/// The migration is not permissionless, the launchpad program only accepts
+/// `GlobalConfig.migrate_to_cpswap_wallet` as the payer,
+/// so third party keepers can't migrate the pools of a global config
+#[derive(Accounts)]
+pub struct MigrateToCpswap<'info> {
+    /// Only migrate_to_cpswap_wallet can migrate to cpswap pool, the same check as the launchpad program
+    #[account(
+        mut,
+        address = global_config.migrate_to_cpswap_wallet
+    )]
+    pub payer: Signer<'info>,
+
+    /// The mint of the base token
+    #[account(
+        address = pool_state.base_mint
+    )]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of the quote token
+    #[account(
+        address = pool_state.quote_mint
+    )]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Platform configuration account containing the cpswap config and the lp scales
+    #[account(
+        address = pool_state.platform_config
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// CHECK: CP-Swap program
+    #[account(
+        address = crate::cpswap_program::ID
+    )]
+    pub cpswap_program: UncheckedAccount<'info>,
+
+    /// CHECK: The cpswap pool state, initialized by the CP-Swap program
+    #[account(mut)]
+    pub cpswap_pool: UncheckedAccount<'info>,
+
+    /// CHECK: The cpswap vault and lp mint authority, checked by the CP-Swap program
+    pub cpswap_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The cpswap lp mint, initialized by the CP-Swap program
+    #[account(mut)]
+    pub cpswap_lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: The cpswap base vault, initialized by the CP-Swap program
+    #[account(mut)]
+    pub cpswap_base_vault: UncheckedAccount<'info>,
+
+    /// CHECK: The cpswap quote vault, initialized by the CP-Swap program
+    #[account(mut)]
+    pub cpswap_quote_vault: UncheckedAccount<'info>,
+
+    /// CHECK: The amm config of the cpswap pool
+    #[account(
+        address = platform_config.cpswap_config
+    )]
+    pub cpswap_config: UncheckedAccount<'info>,
+
+    /// CHECK: The pool creation fee receiver of the CP-Swap program
+    #[account(mut)]
+    pub cpswap_create_pool_fee: UncheckedAccount<'info>,
+
+    /// CHECK: The cpswap oracle observation, initialized by the CP-Swap program
+    #[account(mut)]
+    pub cpswap_observation: UncheckedAccount<'info>,
+
+    /// CHECK: Liquidity lock program
+    #[account(
+        address = crate::lock_program::ID
+    )]
+    pub lock_program: UncheckedAccount<'info>,
+
+    /// CHECK: The authority of the lock program, checked by the lock program
+    pub lock_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The lp vault of the lock program, checked by the lock program
+    #[account(mut)]
+    pub lock_lp_vault: UncheckedAccount<'info>,
+
+    /// PDA that acts as the authority for pool vault operations
+    /// Generated using AUTH_SEED
+    /// CHECK: This is a PDA, safety checks performed in seeds constraint
+    #[account(
+        mut,
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Account that stores the pool's state and parameters
+    /// PDA generated using POOL_SEED and both token mints
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    /// Global configuration account containing the migrate wallets
+    #[account(
+        address = pool_state.global_config
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// The pool's vault for base tokens
+    #[account(
+        mut,
+        address = pool_state.base_vault
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool's vault for quote tokens
+    #[account(
+        mut,
+        address = pool_state.quote_vault
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The lp token account of the authority, created by the CP-Swap program
+    #[account(mut)]
+    pub pool_lp_token: UncheckedAccount<'info>,
+
+    /// SPL Token program for the base token
+    pub base_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL Token program for the quote token
+    /// Must be the standard Token program
+    #[account(
+        address = spl_token::id()
+    )]
+    pub quote_token_program: Program<'info, Token>,
+
+    /// Required for associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Required for account creation
+    pub system_program: Program<'info, System>,
+
+    /// Required for rent exempt calculations
+    pub rent_program: Sysvar<'info, Rent>,
+
+    /// Program to create the lock NFT metadata account
+    pub metadata_program: Program<'info, Metadata>,
+}
+
 #[derive(Accounts)]
 pub struct CreatePlatformConfig<'info> {
     /// The account paying for the initialization costs  
@@ -912,3 +1241,221 @@ pub struct ClaimCreatorFee<'info> {
     /// Required for associated token program
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
+
+/// Accounts required for creating a global config
+#[cfg(feature = "admin")]
+#[derive(Accounts)]
+#[instruction(curve_type: u8, index: u16)]
+pub struct CreateConfig<'info> {
+    /// The protocol owner/admin account
+    /// Must match the predefined admin address
+    /// Has authority to create and modify protocol configurations
+    #[account(
+        mut,
+        address = crate::admin::ID
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global configuration account that stores protocol-wide settings
+    /// PDA generated using GLOBAL_CONFIG_SEED, quote token mint, curve type and index
+    #[account(
+        init,
+        seeds = [
+            GLOBAL_CONFIG_SEED.as_bytes(),
+            quote_token_mint.key().as_ref(),
+            &curve_type.to_le_bytes(),
+            &index.to_le_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = GlobalConfig::LEN
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The mint address of the quote token (token used for buying)
+    pub quote_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: Account that will receive protocol fees
+    pub protocol_fee_owner: UncheckedAccount<'info>,
+
+    /// CHECK: Account that will receive migrate fees
+    pub migrate_fee_owner: UncheckedAccount<'info>,
+
+    /// CHECK: The control wallet address for migrating to amm
+    pub migrate_to_amm_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: The control wallet address for migrating to cpswap
+    pub migrate_to_cpswap_wallet: UncheckedAccount<'info>,
+
+    /// Required for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts required for updating a global config
+#[cfg(feature = "admin")]
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    /// The global config owner or admin
+    #[account(
+        address = crate::admin::ID
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global config account to be changed
+    #[account(mut)]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+/// Accounts required for collecting the protocol fee
+#[cfg(feature = "admin")]
+#[derive(Accounts)]
+pub struct CollectFee<'info> {
+    /// Only protocol_fee_owner saved in global_config can collect protocol fee now
+    #[account(
+        address = global_config.protocol_fee_owner
+    )]
+    pub owner: Signer<'info>,
+
+    /// PDA that acts as the authority for pool vault and mint operations
+    /// Generated using AUTH_SEED
+    /// CHECK: This is a PDA, safety checks performed in seeds constraint
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Account that stores the pool's state and parameters
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    /// Global config account stores owner
+    #[account(
+        address = pool_state.global_config
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// The address that holds pool tokens for quote token
+    #[account(
+        mut,
+        address = pool_state.quote_vault
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token vault
+    #[account(
+        address = quote_vault.mint
+    )]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The address that receives the collected quote token fees
+    #[account(
+        mut,
+        token::mint = quote_mint,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for input token transfers
+    pub token_program: Program<'info, Token>,
+}
+
+/// Accounts required for collecting the migrate fee
+#[cfg(feature = "admin")]
+#[derive(Accounts)]
+pub struct CollectMigrateFee<'info> {
+    /// Only migrate_fee_owner saved in global_config can collect migrate fee now
+    #[account(
+        address = global_config.migrate_fee_owner
+    )]
+    pub owner: Signer<'info>,
+
+    /// PDA that acts as the authority for pool vault and mint operations
+    /// Generated using AUTH_SEED
+    /// CHECK: This is a PDA, safety checks performed in seeds constraint
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Account that stores the pool's state and parameters
+    #[account(mut)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    /// Global config account stores owner
+    #[account(
+        address = pool_state.global_config
+    )]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    /// The address that holds pool tokens for quote token
+    #[account(
+        mut,
+        address = pool_state.quote_vault
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token vault
+    #[account(
+        address = quote_vault.mint
+    )]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The address that receives the collected quote token fees
+    #[account(
+        mut,
+        token::mint = quote_mint,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for input token transfers
+    pub token_program: Program<'info, Token>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUPPLY: u64 = 1_000_000_000_000_000;
+
+    #[test]
+    fn transfer_fee_basis_points_limit() {
+        let params = TransferFeeExtensionParams::new(500, u64::MAX, SUPPLY).unwrap();
+        assert_eq!(params.transfer_fee_basis_points(), 500);
+        assert_eq!(
+            TransferFeeExtensionParams::new(501, u64::MAX, SUPPLY).err(),
+            Some(ErrorCode::RequireGteViolated.into())
+        );
+    }
+
+    #[test]
+    fn maximum_fee_must_exceed_supply_fee() {
+        // 5% of the supply
+        let supply_fee = 50_000_000_000_000;
+        assert_eq!(
+            TransferFeeExtensionParams::new(500, supply_fee, SUPPLY).err(),
+            Some(ErrorCode::RequireGtViolated.into())
+        );
+        let params = TransferFeeExtensionParams::new(500, supply_fee + 1, SUPPLY).unwrap();
+        assert_eq!(params.maximum_fee, supply_fee + 1);
+
+        // The supply fee is rounded down
+        assert!(TransferFeeExtensionParams::new(1, 1, 9_999).is_ok());
+        assert!(TransferFeeExtensionParams::new(1, 1, 10_000).is_err());
+        assert!(TransferFeeExtensionParams::new(500, u64::MAX, u64::MAX).is_ok());
+        assert!(TransferFeeExtensionParams::new(0, 0, SUPPLY).is_err());
+    }
+
+    #[test]
+    fn serialized_layout() {
+        let params = TransferFeeExtensionParams::new(100, 7, 0).unwrap();
+        let data = params.try_to_vec().unwrap();
+        assert_eq!(data, [100, 0, 7, 0, 0, 0, 0, 0, 0, 0]);
+        let params = TransferFeeExtensionParams::try_from_slice(&data).unwrap();
+        assert_eq!(params.transfer_fee_basis_points(), 100);
+    }
+}
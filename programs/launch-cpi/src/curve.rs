@@ -0,0 +1,364 @@
+//! Bonding curve math with the same rounding as the launchpad program.
+//!
+//! All amounts are without fees, the fees are charged on the quote side
+//! before a buy and after a sell. The amounts are not capped by the base
+//! left to sell, the program ends the fundraising with a smaller trade
+//! once `real_base` would exceed `total_base_sell`, see `fees::buy_exact_in`.
+use crate::states::{GlobalConfig, PoolState};
+use big_num::U256;
+
+#[allow(clippy::assign_op_pattern, clippy::manual_div_ceil)]
+mod big_num {
+    use uint::construct_uint;
+
+    construct_uint! {
+        /// 256 bit unsigned integer for the intermediate products of the linear curve
+        pub struct U256(4);
+    }
+}
+
+/// Q64.64 fixed point one, the scale of the linear curve slope
+pub const Q64: u128 = 1 << 64;
+
+/// The curve type of a global config, see `GlobalConfig.curve_type`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveType {
+    /// Constant product of the virtual and real reserves
+    Constant = 0,
+    /// Fixed price of `virtual_quote / virtual_base`
+    Fixed = 1,
+    /// Price linear in the base sold, with slope `virtual_base / 2^64`
+    Linear = 2,
+}
+
+impl CurveType {
+    /// Decode the curve type, returns `None` on an unknown value
+    pub fn from_u8(curve_type: u8) -> Option<Self> {
+        Some(match curve_type {
+            0 => CurveType::Constant,
+            1 => CurveType::Fixed,
+            2 => CurveType::Linear,
+            _ => return None,
+        })
+    }
+
+    /// The raw value of the curve type
+    pub fn into_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+/// The curve of a pool with its current reserves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Curve {
+    /// The curve type of the global config of the pool
+    pub curve_type: CurveType,
+    /// `PoolState.virtual_base`
+    pub virtual_base: u64,
+    /// `PoolState.virtual_quote`
+    pub virtual_quote: u64,
+    /// `PoolState.real_base`, the base amount sold
+    pub real_base: u64,
+    /// `PoolState.real_quote`, the quote amount raised
+    pub real_quote: u64,
+}
+
+impl Curve {
+    /// Build the curve of `pool_state`, returns `None` on an unknown curve type
+    pub fn new(global_config: &GlobalConfig, pool_state: &PoolState) -> Option<Self> {
+        Some(Self {
+            curve_type: CurveType::from_u8(global_config.curve_type)?,
+            virtual_base: pool_state.virtual_base,
+            virtual_quote: pool_state.virtual_quote,
+            real_base: pool_state.real_base,
+            real_quote: pool_state.real_quote,
+        })
+    }
+
+    /// Base amount out for the given quote amount in, rounded down
+    pub fn buy_exact_in(&self, amount_in: u64) -> Option<u64> {
+        let amount_out = match self.curve_type {
+            CurveType::Constant => constant::get_amount_out(
+                u128::from(amount_in),
+                self.constant_quote_reserve()?,
+                self.constant_base_reserve()?,
+            )?,
+            CurveType::Fixed => fixed::get_amount_out(
+                u128::from(amount_in),
+                u128::from(self.virtual_quote),
+                u128::from(self.virtual_base),
+            )?,
+            CurveType::Linear => {
+                let new_quote = u128::from(self.real_quote).checked_add(u128::from(amount_in))?;
+                linear::base_for_quote(self.virtual_base, new_quote)?
+                    .checked_sub(u128::from(self.real_base))?
+            }
+        };
+        u64::try_from(amount_out).ok()
+    }
+
+    /// Quote amount in for the given base amount out, rounded up
+    pub fn buy_exact_out(&self, amount_out: u64) -> Option<u64> {
+        let amount_in = match self.curve_type {
+            CurveType::Constant => constant::get_amount_in(
+                u128::from(amount_out),
+                self.constant_quote_reserve()?,
+                self.constant_base_reserve()?,
+            )?,
+            CurveType::Fixed => fixed::get_amount_in(
+                u128::from(amount_out),
+                u128::from(self.virtual_quote),
+                u128::from(self.virtual_base),
+            )?,
+            CurveType::Linear => {
+                let new_base = u128::from(self.real_base).checked_add(u128::from(amount_out))?;
+                linear::quote_for_base(self.virtual_base, new_base)?
+                    .checked_sub(u128::from(self.real_quote))?
+            }
+        };
+        u64::try_from(amount_in).ok()
+    }
+
+    /// Quote amount out for the given base amount in, rounded down
+    pub fn sell_exact_in(&self, amount_in: u64) -> Option<u64> {
+        let amount_out = match self.curve_type {
+            CurveType::Constant => constant::get_amount_out(
+                u128::from(amount_in),
+                self.constant_base_reserve()?,
+                self.constant_quote_reserve()?,
+            )?,
+            CurveType::Fixed => fixed::get_amount_out(
+                u128::from(amount_in),
+                u128::from(self.virtual_base),
+                u128::from(self.virtual_quote),
+            )?,
+            CurveType::Linear => {
+                let new_base = u128::from(self.real_base).checked_sub(u128::from(amount_in))?;
+                u128::from(self.real_quote)
+                    .checked_sub(linear::quote_for_base(self.virtual_base, new_base)?)?
+            }
+        };
+        u64::try_from(amount_out).ok()
+    }
+
+    /// Base amount in for the given quote amount out, rounded up
+    pub fn sell_exact_out(&self, amount_out: u64) -> Option<u64> {
+        let amount_in = match self.curve_type {
+            CurveType::Constant => constant::get_amount_in(
+                u128::from(amount_out),
+                self.constant_base_reserve()?,
+                self.constant_quote_reserve()?,
+            )?,
+            CurveType::Fixed => fixed::get_amount_in(
+                u128::from(amount_out),
+                u128::from(self.virtual_base),
+                u128::from(self.virtual_quote),
+            )?,
+            CurveType::Linear => {
+                let new_quote = u128::from(self.real_quote).checked_sub(u128::from(amount_out))?;
+                u128::from(self.real_base)
+                    .checked_sub(linear::base_for_quote(self.virtual_base, new_quote)?)?
+            }
+        };
+        u64::try_from(amount_in).ok()
+    }
+
+    fn constant_base_reserve(&self) -> Option<u128> {
+        u128::from(self.virtual_base).checked_sub(u128::from(self.real_base))
+    }
+
+    fn constant_quote_reserve(&self) -> Option<u128> {
+        u128::from(self.virtual_quote).checked_add(u128::from(self.real_quote))
+    }
+}
+
+/// Constant product on `virtual_base - real_base` and `virtual_quote + real_quote`
+mod constant {
+    pub fn get_amount_out(
+        amount_in: u128,
+        input_reserve: u128,
+        output_reserve: u128,
+    ) -> Option<u128> {
+        amount_in
+            .checked_mul(output_reserve)?
+            .checked_div(input_reserve.checked_add(amount_in)?)
+    }
+
+    pub fn get_amount_in(
+        amount_out: u128,
+        input_reserve: u128,
+        output_reserve: u128,
+    ) -> Option<u128> {
+        super::checked_ceil_div(
+            input_reserve.checked_mul(amount_out)?,
+            output_reserve.checked_sub(amount_out)?,
+        )
+    }
+}
+
+/// Fixed price of `virtual_quote / virtual_base`
+mod fixed {
+    pub fn get_amount_out(amount_in: u128, init_input: u128, init_output: u128) -> Option<u128> {
+        init_output.checked_mul(amount_in)?.checked_div(init_input)
+    }
+
+    pub fn get_amount_in(amount_out: u128, init_input: u128, init_output: u128) -> Option<u128> {
+        super::checked_ceil_div(init_input.checked_mul(amount_out)?, init_output)
+    }
+}
+
+/// Price `a * x` with `a = virtual_base / 2^64` and `x` the base sold, so the quote
+/// raised at `x` is `a * x^2 / 2`. The products exceed 128 bits, they are computed
+/// on 256 bits like the program does.
+mod linear {
+    use super::{Q64, U256};
+
+    /// Base sold when `quote` is raised, rounded down
+    pub fn base_for_quote(virtual_base: u64, quote: u128) -> Option<u128> {
+        let term = U256::from(quote)
+            .checked_mul(U256::from(Q64))?
+            .checked_mul(U256::from(2))?
+            .checked_div(U256::from(virtual_base))?;
+        u128::try_from(term.integer_sqrt()).ok()
+    }
+
+    /// Quote raised when `base` is sold, rounded up
+    pub fn quote_for_base(virtual_base: u64, base: u128) -> Option<u128> {
+        let numerator = U256::from(virtual_base)
+            .checked_mul(U256::from(base))?
+            .checked_mul(U256::from(base))?;
+        let denominator = U256::from(Q64).checked_mul(U256::from(2))?;
+        let (quotient, remainder) = numerator.div_mod(denominator);
+        let quotient = u128::try_from(quotient).ok()?;
+        if remainder.is_zero() {
+            Some(quotient)
+        } else {
+            quotient.checked_add(1)
+        }
+    }
+}
+
+fn checked_ceil_div(numerator: u128, denominator: u128) -> Option<u128> {
+    let quotient = numerator.checked_div(denominator)?;
+    if numerator.checked_rem(denominator)? > 0 {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOTAL_BASE_SELL: u64 = 793_100_000_000_000;
+
+    fn curve(curve_type: CurveType, virtual_base: u64, virtual_quote: u64) -> Curve {
+        Curve {
+            curve_type,
+            virtual_base,
+            virtual_quote,
+            real_base: 0,
+            real_quote: 0,
+        }
+    }
+
+    #[test]
+    fn curve_type_from_u8() {
+        for curve_type in [CurveType::Constant, CurveType::Fixed, CurveType::Linear] {
+            assert_eq!(CurveType::from_u8(curve_type.into_u8()), Some(curve_type));
+        }
+        assert_eq!(CurveType::from_u8(3), None);
+    }
+
+    #[test]
+    fn constant_curve() {
+        let curve = curve(CurveType::Constant, 1_073_025_605_596_382, 30_000_852_951);
+        assert_eq!(curve.buy_exact_in(1_000_000_000), Some(34_612_776_857_862));
+        assert_eq!(curve.buy_exact_out(34_612_776_857_862), Some(1_000_000_000));
+        assert_eq!(curve.buy_exact_in(1), Some(35_766));
+        assert_eq!(curve.buy_exact_out(1), Some(1));
+        assert_eq!(curve.buy_exact_out(TOTAL_BASE_SELL), Some(85_000_000_000));
+
+        let curve = Curve {
+            real_base: 34_612_776_857_862,
+            real_quote: 1_000_000_000,
+            ..curve
+        };
+        assert_eq!(curve.sell_exact_in(34_612_776_857_862), Some(999_999_999));
+        assert_eq!(
+            curve.sell_exact_out(1_000_000_000),
+            Some(34_612_776_857_863)
+        );
+
+        let curve = Curve {
+            real_base: TOTAL_BASE_SELL,
+            real_quote: 85_000_000_000,
+            ..curve
+        };
+        assert_eq!(curve.sell_exact_in(TOTAL_BASE_SELL), Some(84_999_999_999));
+        assert_eq!(curve.sell_exact_in(1), Some(0));
+        assert_eq!(curve.sell_exact_out(1), Some(2_435));
+    }
+
+    #[test]
+    fn fixed_curve() {
+        let curve = curve(CurveType::Fixed, 1000, 3);
+        assert_eq!(curve.buy_exact_in(10), Some(3_333));
+        assert_eq!(curve.buy_exact_out(3_333), Some(10));
+        assert_eq!(curve.buy_exact_in(1), Some(333));
+        assert_eq!(curve.buy_exact_out(1), Some(1));
+        assert_eq!(curve.buy_exact_in(9_000), Some(3_000_000));
+        assert_eq!(curve.buy_exact_out(3_000_000), Some(9_000));
+
+        let curve = Curve {
+            real_base: 3_000_000,
+            real_quote: 9_000,
+            ..curve
+        };
+        assert_eq!(curve.sell_exact_in(3_333), Some(9));
+        assert_eq!(curve.sell_exact_out(10), Some(3_334));
+        assert_eq!(curve.sell_exact_in(1), Some(0));
+        assert_eq!(curve.sell_exact_out(1), Some(334));
+        assert_eq!(curve.sell_exact_in(3_000_000), Some(9_000));
+        assert_eq!(curve.sell_exact_out(9_000), Some(3_000_000));
+    }
+
+    #[test]
+    fn linear_curve() {
+        // The products of the full raise exceed 128 bits with this slope
+        let curve = curve(CurveType::Linear, 1 << 30, 0);
+        assert_eq!(curve.buy_exact_in(1_000_000_000), Some(5_861_718_038));
+        assert_eq!(curve.buy_exact_out(5_861_718_038), Some(1_000_000_000));
+        assert_eq!(curve.buy_exact_in(1), Some(185_363));
+        assert_eq!(curve.buy_exact_out(1), Some(1));
+        assert_eq!(
+            curve.buy_exact_in(18_306_530_837_435_275_317),
+            Some(TOTAL_BASE_SELL)
+        );
+        assert_eq!(
+            curve.buy_exact_out(TOTAL_BASE_SELL),
+            Some(18_306_530_837_435_275_317)
+        );
+
+        let curve = Curve {
+            real_base: TOTAL_BASE_SELL,
+            real_quote: 18_306_530_837_435_275_317,
+            ..curve
+        };
+        assert_eq!(curve.sell_exact_in(1), Some(46_165));
+        assert_eq!(curve.sell_exact_out(1), Some(1));
+        assert_eq!(
+            curve.sell_exact_in(TOTAL_BASE_SELL),
+            Some(18_306_530_837_435_275_317)
+        );
+        assert_eq!(
+            curve.sell_exact_out(18_306_530_837_435_275_317),
+            Some(TOTAL_BASE_SELL)
+        );
+        // More than raised or sold
+        assert_eq!(curve.sell_exact_in(TOTAL_BASE_SELL + 1), None);
+        assert_eq!(curve.sell_exact_out(18_306_530_837_435_275_318), None);
+    }
+}
@@ -0,0 +1,327 @@
+use crate::states::*;
+use anchor_lang::{event::EVENT_IX_TAG_LE, prelude::*, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Prefix of the log line written by `emit!`
+pub const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// The direction of a trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum TradeDirection {
+    /// Quote token in, base token out
+    Buy,
+    /// Base token in, quote token out
+    Sell,
+}
+
+/// Emitted by self-CPI when a trade is made
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TradeEvent {
+    pub pool_state: Pubkey,
+    pub total_base_sell: u64,
+    pub virtual_base: u64,
+    pub virtual_quote: u64,
+    pub real_base_before: u64,
+    pub real_quote_before: u64,
+    pub real_base_after: u64,
+    pub real_quote_after: u64,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub protocol_fee: u64,
+    pub platform_fee: u64,
+    pub creator_fee: u64,
+    pub share_fee: u64,
+    pub trade_direction: TradeDirection,
+    /// The pool status after the trade, `Migrate` once the fundraising ends
+    pub pool_status: PoolStatus,
+    pub exact_in: bool,
+}
+
+/// Emitted by self-CPI when a pool is created
+#[event]
+#[derive(Clone, Debug)]
+pub struct PoolCreateEvent {
+    pub pool_state: Pubkey,
+    pub creator: Pubkey,
+    pub config: Pubkey,
+    pub base_mint_param: MintParams,
+    pub curve_param: CurveParams,
+    pub vesting_param: VestingParams,
+    pub amm_fee_on: AmmCreatorFeeOn,
+}
+
+/// Emitted when a vesting account is created
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateVestingEvent {
+    pub pool_state: Pubkey,
+    pub beneficiary: Pubkey,
+    pub share_amount: u64,
+}
+
+/// Emitted when vested tokens are claimed
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClaimVestedEvent {
+    pub pool_state: Pubkey,
+    pub beneficiary: Pubkey,
+    pub claim_amount: u64,
+}
+
+/// All events emitted by the launchpad program
+#[derive(Clone, Debug)]
+pub enum LaunchpadEvent {
+    Trade(TradeEvent),
+    PoolCreate(PoolCreateEvent),
+    CreateVesting(CreateVestingEvent),
+    ClaimVested(ClaimVestedEvent),
+}
+
+impl LaunchpadEvent {
+    /// Decode an event from its discriminator prefixed data,
+    /// returns `None` if the data is not a known launchpad event
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if let Some(mut rest) = data.strip_prefix(TradeEvent::DISCRIMINATOR) {
+            return TradeEvent::deserialize(&mut rest).ok().map(Self::Trade);
+        }
+        if let Some(mut rest) = data.strip_prefix(PoolCreateEvent::DISCRIMINATOR) {
+            return PoolCreateEvent::deserialize(&mut rest)
+                .ok()
+                .map(Self::PoolCreate);
+        }
+        if let Some(mut rest) = data.strip_prefix(CreateVestingEvent::DISCRIMINATOR) {
+            return CreateVestingEvent::deserialize(&mut rest)
+                .ok()
+                .map(Self::CreateVesting);
+        }
+        if let Some(mut rest) = data.strip_prefix(ClaimVestedEvent::DISCRIMINATOR) {
+            return ClaimVestedEvent::deserialize(&mut rest)
+                .ok()
+                .map(Self::ClaimVested);
+        }
+        None
+    }
+
+    /// Decode an event from a single `Program data: <base64>` log line
+    pub fn from_log(log: &str) -> Option<Self> {
+        let encoded = log.strip_prefix(PROGRAM_DATA_LOG_PREFIX)?;
+        let data = STANDARD.decode(encoded.trim()).ok()?;
+        Self::decode(&data)
+    }
+
+    /// Decode an event from the data of a self-CPI event instruction,
+    /// i.e. `EVENT_IX_TAG_LE` followed by the event discriminator and data.
+    /// `TradeEvent` and `PoolCreateEvent` are only emitted this way.
+    pub fn from_cpi_instruction_data(data: &[u8]) -> Option<Self> {
+        Self::decode(data.strip_prefix(EVENT_IX_TAG_LE)?)
+    }
+
+    /// Decode all events from the log messages of a transaction.
+    ///
+    /// Only `Program data:` lines written while the launchpad program is the
+    /// innermost invoked program are decoded, so events of other programs
+    /// sharing the same transaction are skipped.
+    pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<Self> {
+        parse_program_logs(&crate::id(), logs, Self::from_log)
+    }
+}
+
+/// Call `decode` on the lines written while `program_id` is the innermost invoked program,
+/// tracking the invocation stack from the `Program <id> invoke`, `success` and `failed` lines.
+fn parse_program_logs<T, S: AsRef<str>>(
+    program_id: &Pubkey,
+    logs: &[S],
+    mut decode: impl FnMut(&str) -> Option<T>,
+) -> Vec<T> {
+    let program_id = program_id.to_string();
+    // Whether each frame of the invocation stack is `program_id`
+    let mut invoke_stack: Vec<bool> = Vec::new();
+    let mut decoded = Vec::new();
+    for log in logs {
+        let log = log.as_ref();
+        let mut parts = log.split_whitespace();
+        if let (Some("Program"), Some(id), Some(status)) =
+            (parts.next(), parts.next(), parts.next())
+        {
+            // `Program log:`, `Program data:` and so on are written by the program
+            if !id.ends_with(':') {
+                if status == "invoke" {
+                    invoke_stack.push(id == program_id);
+                } else if status == "success" || status.starts_with("failed") {
+                    invoke_stack.pop();
+                }
+                continue;
+            }
+        }
+        if invoke_stack.last() == Some(&true) {
+            decoded.extend(decode(log));
+        }
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+
+    const OTHER_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+    fn trade_event() -> TradeEvent {
+        TradeEvent {
+            pool_state: Pubkey::new_unique(),
+            total_base_sell: 793_100_000_000_000,
+            virtual_base: 1_073_025_605_596_382,
+            virtual_quote: 30_000_852_951,
+            real_base_before: 0,
+            real_quote_before: 0,
+            real_base_after: 34_612_776_857_862,
+            real_quote_after: 1_000_000_000,
+            amount_in: 1_002_500_000,
+            amount_out: 34_612_776_857_862,
+            protocol_fee: 2_500_000,
+            platform_fee: 0,
+            creator_fee: 0,
+            share_fee: 0,
+            trade_direction: TradeDirection::Buy,
+            pool_status: PoolStatus::Fund,
+            exact_in: true,
+        }
+    }
+
+    fn pool_create_event() -> PoolCreateEvent {
+        PoolCreateEvent {
+            pool_state: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            config: Pubkey::new_unique(),
+            base_mint_param: MintParams {
+                decimals: 6,
+                name: "Token".to_string(),
+                symbol: "TKN".to_string(),
+                uri: "https://example.com/token.json".to_string(),
+            },
+            curve_param: CurveParams::Constant {
+                data: ConstantCurve {
+                    supply: 1_000_000_000_000_000,
+                    total_base_sell: 793_100_000_000_000,
+                    total_quote_fund_raising: 85_000_000_000,
+                    migrate_type: 1,
+                },
+            },
+            vesting_param: VestingParams {
+                total_locked_amount: 0,
+                cliff_period: 0,
+                unlock_period: 0,
+            },
+            amm_fee_on: AmmCreatorFeeOn::QuoteToken,
+        }
+    }
+
+    fn claim_vested_event() -> ClaimVestedEvent {
+        ClaimVestedEvent {
+            pool_state: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            claim_amount: 400,
+        }
+    }
+
+    fn cpi_instruction_data(data: &[u8]) -> Vec<u8> {
+        let mut ix_data = EVENT_IX_TAG_LE.to_vec();
+        ix_data.extend(data);
+        ix_data
+    }
+
+    fn data_log(data: &[u8]) -> String {
+        format!("{}{}", PROGRAM_DATA_LOG_PREFIX, STANDARD.encode(data))
+    }
+
+    fn assert_pool_create(event: Option<LaunchpadEvent>, expected: &PoolCreateEvent) {
+        let Some(LaunchpadEvent::PoolCreate(event)) = event else {
+            panic!("not a pool create event: {:?}", event);
+        };
+        assert_eq!(event.try_to_vec().unwrap(), expected.try_to_vec().unwrap());
+    }
+
+    #[test]
+    fn from_cpi_instruction_data_decodes_trade_and_pool_create() {
+        let trade = trade_event();
+        let data = cpi_instruction_data(&trade.data());
+        let Some(LaunchpadEvent::Trade(event)) = LaunchpadEvent::from_cpi_instruction_data(&data)
+        else {
+            panic!("not a trade event");
+        };
+        assert_eq!(event, trade);
+
+        let pool_create = pool_create_event();
+        assert_pool_create(
+            LaunchpadEvent::from_cpi_instruction_data(&cpi_instruction_data(&pool_create.data())),
+            &pool_create,
+        );
+    }
+
+    #[test]
+    fn from_cpi_instruction_data_rejects_invalid_data() {
+        let data = trade_event().data();
+        // Missing `EVENT_IX_TAG_LE`
+        assert!(LaunchpadEvent::from_cpi_instruction_data(&data).is_none());
+        // Truncated payload
+        let ix_data = cpi_instruction_data(&data);
+        assert!(LaunchpadEvent::from_cpi_instruction_data(&ix_data[..ix_data.len() - 1]).is_none());
+        // Unknown discriminator
+        let mut unknown = data.clone();
+        unknown[0] ^= 0xff;
+        assert!(
+            LaunchpadEvent::from_cpi_instruction_data(&cpi_instruction_data(&unknown)).is_none()
+        );
+    }
+
+    #[test]
+    fn from_log_decodes_trade_and_pool_create() {
+        let trade = trade_event();
+        let Some(LaunchpadEvent::Trade(event)) = LaunchpadEvent::from_log(&data_log(&trade.data()))
+        else {
+            panic!("not a trade event");
+        };
+        assert_eq!(event, trade);
+
+        let pool_create = pool_create_event();
+        assert_pool_create(
+            LaunchpadEvent::from_log(&data_log(&pool_create.data())),
+            &pool_create,
+        );
+
+        // The event instruction data is not a log payload
+        assert!(
+            LaunchpadEvent::from_log(&data_log(&cpi_instruction_data(&trade.data()))).is_none()
+        );
+        let mut truncated = pool_create.data();
+        truncated.pop();
+        assert!(LaunchpadEvent::from_log(&data_log(&truncated)).is_none());
+    }
+
+    #[test]
+    fn parse_logs_only_decodes_launchpad_frames() {
+        let program_id = crate::id().to_string();
+        let claim_vested = claim_vested_event();
+        let logs = vec![
+            format!("Program {} invoke [1]", OTHER_PROGRAM_ID),
+            data_log(&claim_vested_event().data()),
+            format!("Program {} invoke [2]", program_id),
+            "Program log: Instruction: ClaimVestedToken".to_string(),
+            format!("Program {} invoke [3]", anchor_spl::token::ID),
+            data_log(&claim_vested_event().data()),
+            format!("Program {} success", anchor_spl::token::ID),
+            data_log(&claim_vested.data()),
+            format!("Program {} success", program_id),
+            data_log(&claim_vested_event().data()),
+            format!("Program {} success", OTHER_PROGRAM_ID),
+        ];
+        let events = LaunchpadEvent::parse_logs(&logs);
+        assert_eq!(events.len(), 1);
+        let LaunchpadEvent::ClaimVested(event) = &events[0] else {
+            panic!("not a claim vested event");
+        };
+        assert_eq!(*event, claim_vested);
+    }
+}
@@ -0,0 +1,412 @@
+//! Fee math of the launchpad program, used to project the result of a trade.
+//!
+//! All the fees are charged on the quote token, on top of the curve amount when
+//! buying and out of the curve amount when selling.
+use crate::{
+    curve::Curve,
+    states::{GlobalConfig, PlatformConfig, RATE_DENOMINATOR_VALUE},
+};
+use anchor_lang::prelude::*;
+
+/// The fee rates charged by a trade
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeRates {
+    /// `GlobalConfig.trade_fee_rate`
+    pub protocol_fee_rate: u64,
+    /// `PlatformConfig.fee_rate`
+    pub platform_fee_rate: u64,
+    /// The `share_fee_rate` argument of the trade
+    pub share_fee_rate: u64,
+    /// `PlatformConfig.creator_fee_rate`
+    pub creator_fee_rate: u64,
+}
+
+impl FeeRates {
+    /// The fee rates of a trade with `share_fee_rate`, which must not exceed
+    /// `GlobalConfig.max_share_fee_rate` as the program rejects the trade otherwise
+    pub fn new(
+        global_config: &GlobalConfig,
+        platform_config: &PlatformConfig,
+        share_fee_rate: u64,
+    ) -> Result<Self> {
+        require_gte!(global_config.max_share_fee_rate, share_fee_rate);
+        let fee_rates = Self {
+            protocol_fee_rate: global_config.trade_fee_rate,
+            platform_fee_rate: platform_config.fee_rate,
+            share_fee_rate,
+            creator_fee_rate: platform_config.creator_fee_rate,
+        };
+        require_gte!(
+            RATE_DENOMINATOR_VALUE,
+            fee_rates
+                .total_fee_rate()
+                .ok_or(ErrorCode::InvalidNumericConversion)?
+        );
+        Ok(fee_rates)
+    }
+
+    /// Sum of all the fee rates
+    pub fn total_fee_rate(&self) -> Option<u64> {
+        self.protocol_fee_rate
+            .checked_add(self.platform_fee_rate)?
+            .checked_add(self.share_fee_rate)?
+            .checked_add(self.creator_fee_rate)
+    }
+
+    /// Split a fee charged at `total_fee_rate` into its components. The platform, share and
+    /// creator fees are rounded down, the protocol fee keeps the remainder.
+    pub fn split(&self, total_fee: u64) -> Option<FeeBreakdown> {
+        let total_fee_rate = self.total_fee_rate()?;
+        if total_fee_rate == 0 {
+            return Some(FeeBreakdown {
+                protocol_fee: total_fee,
+                ..Default::default()
+            });
+        }
+        let part = |fee_rate: u64| -> Option<u64> {
+            let fee = floor_div(
+                u128::from(total_fee),
+                u128::from(fee_rate),
+                u128::from(total_fee_rate),
+            )?;
+            u64::try_from(fee).ok()
+        };
+        let platform_fee = part(self.platform_fee_rate)?;
+        let share_fee = part(self.share_fee_rate)?;
+        let creator_fee = part(self.creator_fee_rate)?;
+        Some(FeeBreakdown {
+            protocol_fee: total_fee
+                .checked_sub(platform_fee)?
+                .checked_sub(share_fee)?
+                .checked_sub(creator_fee)?,
+            platform_fee,
+            share_fee,
+            creator_fee,
+        })
+    }
+}
+
+/// The fees of a trade, all in the quote token
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    /// Accrued to `PoolState.quote_protocol_fee`
+    pub protocol_fee: u64,
+    /// Accrued to `PoolState.platform_fee`, or sent to the platform fee vault
+    pub platform_fee: u64,
+    /// Sent to the share fee receiver
+    pub share_fee: u64,
+    /// Sent to the creator fee vault
+    pub creator_fee: u64,
+}
+
+impl FeeBreakdown {
+    /// Sum of all the fees
+    pub fn total_fee(&self) -> Option<u64> {
+        self.protocol_fee
+            .checked_add(self.platform_fee)?
+            .checked_add(self.share_fee)?
+            .checked_add(self.creator_fee)
+    }
+}
+
+/// Result of a projected trade
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TradeResult {
+    /// Amount the user pays, the quote amount including fees when buying
+    pub amount_in: u64,
+    /// Amount the user receives, the quote amount without fees when selling
+    pub amount_out: u64,
+    /// The fees of the trade
+    pub fees: FeeBreakdown,
+}
+
+fn ceil_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
+    token_amount
+        .checked_mul(fee_numerator)?
+        .checked_add(fee_denominator)?
+        .checked_sub(1)?
+        .checked_div(fee_denominator)
+}
+
+fn floor_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
+    token_amount
+        .checked_mul(fee_numerator)?
+        .checked_div(fee_denominator)
+}
+
+/// Fee of an amount at `fee_rate`, rounded up
+pub fn calculate_fee(amount: u64, fee_rate: u64) -> Option<u64> {
+    let fee = ceil_div(
+        u128::from(amount),
+        u128::from(fee_rate),
+        u128::from(RATE_DENOMINATOR_VALUE),
+    )?;
+    u64::try_from(fee).ok()
+}
+
+/// The amount which is `post_fee_amount` after a fee at `fee_rate` is taken, rounded up
+pub fn calculate_pre_fee_amount(post_fee_amount: u64, fee_rate: u64) -> Option<u64> {
+    if fee_rate == 0 {
+        return Some(post_fee_amount);
+    }
+    let denominator = RATE_DENOMINATOR_VALUE.checked_sub(fee_rate)?;
+    let amount = ceil_div(
+        u128::from(post_fee_amount),
+        u128::from(RATE_DENOMINATOR_VALUE),
+        u128::from(denominator),
+    )?;
+    u64::try_from(amount).ok()
+}
+
+/// Project a `buy_exact_in`. When the curve amount exceeds the base left to sell,
+/// the program sells only the rest and charges the matching quote amount.
+///
+/// # Arguments
+///
+/// * `curve` - The curve of the pool
+/// * `total_base_sell` - `PoolState.total_base_sell`
+/// * `fee_rates` - The fee rates of the trade
+/// * `amount_in` - Quote amount in, including fees
+///
+pub fn buy_exact_in(
+    curve: &Curve,
+    total_base_sell: u64,
+    fee_rates: &FeeRates,
+    amount_in: u64,
+) -> Option<TradeResult> {
+    let fee_rate = fee_rates.total_fee_rate()?;
+    let total_fee = calculate_fee(amount_in, fee_rate)?;
+    let amount_out = curve.buy_exact_in(amount_in.checked_sub(total_fee)?)?;
+    let remaining_base = total_base_sell.checked_sub(curve.real_base)?;
+    if amount_out <= remaining_base {
+        return Some(TradeResult {
+            amount_in,
+            amount_out,
+            fees: fee_rates.split(total_fee)?,
+        });
+    }
+    let amount_in_less_fee = curve.buy_exact_out(remaining_base)?;
+    let amount_in = calculate_pre_fee_amount(amount_in_less_fee, fee_rate)?;
+    Some(TradeResult {
+        amount_in,
+        amount_out: remaining_base,
+        fees: fee_rates.split(amount_in.checked_sub(amount_in_less_fee)?)?,
+    })
+}
+
+/// Project a `buy_exact_out`, the amount out is capped by the base left to sell.
+///
+/// # Arguments
+///
+/// * `curve` - The curve of the pool
+/// * `total_base_sell` - `PoolState.total_base_sell`
+/// * `fee_rates` - The fee rates of the trade
+/// * `amount_out` - Base amount out
+///
+pub fn buy_exact_out(
+    curve: &Curve,
+    total_base_sell: u64,
+    fee_rates: &FeeRates,
+    amount_out: u64,
+) -> Option<TradeResult> {
+    let remaining_base = total_base_sell.checked_sub(curve.real_base)?;
+    let amount_out = amount_out.min(remaining_base);
+    let amount_in_less_fee = curve.buy_exact_out(amount_out)?;
+    let amount_in = calculate_pre_fee_amount(amount_in_less_fee, fee_rates.total_fee_rate()?)?;
+    Some(TradeResult {
+        amount_in,
+        amount_out,
+        fees: fee_rates.split(amount_in.checked_sub(amount_in_less_fee)?)?,
+    })
+}
+
+/// Project a `sell_exact_in`.
+///
+/// # Arguments
+///
+/// * `curve` - The curve of the pool
+/// * `fee_rates` - The fee rates of the trade
+/// * `amount_in` - Base amount in
+///
+pub fn sell_exact_in(curve: &Curve, fee_rates: &FeeRates, amount_in: u64) -> Option<TradeResult> {
+    let amount_out_with_fee = curve.sell_exact_in(amount_in)?;
+    let total_fee = calculate_fee(amount_out_with_fee, fee_rates.total_fee_rate()?)?;
+    Some(TradeResult {
+        amount_in,
+        amount_out: amount_out_with_fee.checked_sub(total_fee)?,
+        fees: fee_rates.split(total_fee)?,
+    })
+}
+
+/// Project a `sell_exact_out`, returns `None` if the pool has not raised enough quote.
+///
+/// # Arguments
+///
+/// * `curve` - The curve of the pool
+/// * `fee_rates` - The fee rates of the trade
+/// * `amount_out` - Quote amount out, without fees
+///
+pub fn sell_exact_out(curve: &Curve, fee_rates: &FeeRates, amount_out: u64) -> Option<TradeResult> {
+    let amount_out_with_fee = calculate_pre_fee_amount(amount_out, fee_rates.total_fee_rate()?)?;
+    if amount_out_with_fee > curve.real_quote {
+        return None;
+    }
+    let amount_in = curve.sell_exact_out(amount_out_with_fee)?;
+    if amount_in > curve.real_base {
+        return None;
+    }
+    Some(TradeResult {
+        amount_in,
+        amount_out,
+        fees: fee_rates.split(amount_out_with_fee.checked_sub(amount_out)?)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::CurveType;
+
+    const FEE_RATES: FeeRates = FeeRates {
+        protocol_fee_rate: 2_500,
+        platform_fee_rate: 5_000,
+        share_fee_rate: 1_500,
+        creator_fee_rate: 1_000,
+    };
+
+    fn platform_config(fee_rate: u64, creator_fee_rate: u64) -> PlatformConfig {
+        let mut platform_config =
+            PlatformConfig::deserialize(&mut &[0u8; PlatformConfig::MIN_LEN - 8][..]).unwrap();
+        platform_config.fee_rate = fee_rate;
+        platform_config.creator_fee_rate = creator_fee_rate;
+        platform_config
+    }
+
+    fn fixed_curve(real_base: u64, real_quote: u64) -> Curve {
+        Curve {
+            curve_type: CurveType::Fixed,
+            virtual_base: 1000,
+            virtual_quote: 3,
+            real_base,
+            real_quote,
+        }
+    }
+
+    #[test]
+    fn fee_rates_new() {
+        let global_config = GlobalConfig {
+            trade_fee_rate: 2_500,
+            max_share_fee_rate: 1_500,
+            ..Default::default()
+        };
+        assert_eq!(
+            FeeRates::new(&global_config, &platform_config(5_000, 1_000), 1_500).unwrap(),
+            FEE_RATES
+        );
+        assert_eq!(
+            FeeRates::new(&global_config, &platform_config(5_000, 1_000), 1_501).unwrap_err(),
+            ErrorCode::RequireGteViolated.into()
+        );
+        // The total rate may reach but not exceed the denominator
+        assert!(FeeRates::new(&global_config, &platform_config(996_500, 1_000), 0).is_ok());
+        assert_eq!(
+            FeeRates::new(&global_config, &platform_config(996_501, 1_000), 0).unwrap_err(),
+            ErrorCode::RequireGteViolated.into()
+        );
+        assert_eq!(
+            FeeRates::new(&global_config, &platform_config(u64::MAX, 1_000), 0).unwrap_err(),
+            ErrorCode::InvalidNumericConversion.into()
+        );
+    }
+
+    #[test]
+    fn split_rounding() {
+        // Exact parts are 58.1, 116.3, 34.9 and 23.3
+        let fees = FEE_RATES.split(233).unwrap();
+        assert_eq!(
+            fees,
+            FeeBreakdown {
+                protocol_fee: 60,
+                platform_fee: 116,
+                share_fee: 34,
+                creator_fee: 23,
+            }
+        );
+        assert_eq!(fees.total_fee(), Some(233));
+        assert_eq!(
+            FEE_RATES.split(1).unwrap(),
+            FeeBreakdown {
+                protocol_fee: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            FeeRates::default().split(7).unwrap(),
+            FeeBreakdown {
+                protocol_fee: 7,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn fee_round_trip() {
+        assert_eq!(calculate_fee(1_000_000, 2_500), Some(2_500));
+        assert_eq!(calculate_fee(1, 2_500), Some(1));
+        assert_eq!(calculate_fee(0, 2_500), Some(0));
+        assert_eq!(calculate_pre_fee_amount(997_500, 2_500), Some(1_000_000));
+        assert_eq!(calculate_pre_fee_amount(1, RATE_DENOMINATOR_VALUE), None);
+        assert_eq!(
+            calculate_pre_fee_amount(1, RATE_DENOMINATOR_VALUE + 1),
+            None
+        );
+
+        for fee_rate in [0, 1, 2_500, 10_000, 999_999] {
+            for post_fee_amount in [0, 1, 7, 999, 123_456_789, u64::MAX / 1_000_000] {
+                // The smallest amount which leaves `post_fee_amount` after the fee
+                let amount = calculate_pre_fee_amount(post_fee_amount, fee_rate).unwrap();
+                let fee = calculate_fee(amount, fee_rate).unwrap();
+                assert!(amount - fee >= post_fee_amount);
+                if amount > 0 {
+                    let fee = calculate_fee(amount - 1, fee_rate).unwrap();
+                    assert!(amount - 1 - fee < post_fee_amount);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn buy_exact_in_capped_at_total_base_sell() {
+        let curve = fixed_curve(0, 0);
+        let trade = buy_exact_in(&curve, 3_000_000, &FEE_RATES, 101).unwrap();
+        assert_eq!((trade.amount_in, trade.amount_out), (101, 33_000));
+        assert_eq!(trade.fees.total_fee(), Some(2));
+
+        // 990_000 net quote would buy 330_000_000 base, only 9_091 quote is charged for the rest
+        let trade = buy_exact_in(&curve, 3_000_000, &FEE_RATES, 1_000_000).unwrap();
+        assert_eq!((trade.amount_in, trade.amount_out), (9_091, 3_000_000));
+        assert_eq!(trade.fees.total_fee(), Some(91));
+        assert_eq!(
+            buy_exact_out(&curve, 3_000_000, &FEE_RATES, u64::MAX),
+            Some(trade)
+        );
+
+        let curve = fixed_curve(3_000_001, 9_000);
+        assert_eq!(buy_exact_in(&curve, 3_000_000, &FEE_RATES, 101), None);
+    }
+
+    #[test]
+    fn sell_exact_out_limited_by_reserves() {
+        let curve = fixed_curve(3_000_000, 9_000);
+        let trade = sell_exact_out(&curve, &FEE_RATES, 9).unwrap();
+        assert_eq!((trade.amount_in, trade.amount_out), (3_334, 9));
+        assert_eq!(trade.fees.total_fee(), Some(1));
+
+        // 9_000 without fees needs 9_091 quote out of the pool
+        assert_eq!(sell_exact_out(&curve, &FEE_RATES, 9_000), None);
+        assert_eq!(
+            sell_exact_out(&fixed_curve(3_000, 9_000), &FEE_RATES, 9),
+            None
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Invokers which fill the conditional remaining accounts of the launchpad instructions.
+
+use crate::states::*;
+use anchor_lang::{error::ErrorCode, prelude::*, system_program};
+
+/// The remaining accounts of `Swap`, in the order the program reads them:
+///
+/// * `share_fee_receiver` - Only if `share_fee_rate > 0`
+/// * `system_program`
+/// * `platform_fee_vault` - Derived from `pool_state.platform_config` and `pool_state.quote_mint`
+/// * `creator_fee_vault` - Derived from `pool_state.creator` and `pool_state.quote_mint`
+///
+/// The last three accounts are required once the fee vault upgrade is live and are
+/// always included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapRemainingAccounts {
+    /// The quote token account receiving the share fee
+    pub share_fee_receiver: Option<Pubkey>,
+    /// The platform fee vault of the pool
+    pub platform_fee_vault: Pubkey,
+    /// The creator fee vault of the pool
+    pub creator_fee_vault: Pubkey,
+}
+
+impl SwapRemainingAccounts {
+    /// Derive the fee vaults of `pool_state`
+    pub fn new(pool_state: &PoolState, share_fee_receiver: Option<Pubkey>) -> Self {
+        Self {
+            share_fee_receiver,
+            platform_fee_vault: platform_fee_vault(
+                &pool_state.platform_config,
+                &pool_state.quote_mint,
+            ),
+            creator_fee_vault: creator_fee_vault(&pool_state.creator, &pool_state.quote_mint),
+        }
+    }
+
+    /// The remaining account metas, to build the instruction off-chain
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        let mut metas = Vec::with_capacity(4);
+        if let Some(share_fee_receiver) = self.share_fee_receiver {
+            metas.push(AccountMeta::new(share_fee_receiver, false));
+        }
+        metas.push(AccountMeta::new_readonly(system_program::ID, false));
+        metas.push(AccountMeta::new(self.platform_fee_vault, false));
+        metas.push(AccountMeta::new(self.creator_fee_vault, false));
+        metas
+    }
+
+    /// Pick the remaining accounts from `accounts`, which may hold them in any order
+    pub fn to_account_infos<'info>(
+        &self,
+        accounts: &[AccountInfo<'info>],
+    ) -> Result<Vec<AccountInfo<'info>>> {
+        self.to_account_metas()
+            .iter()
+            .map(|meta| find_account(accounts, &meta.pubkey))
+            .collect()
+    }
+}
+
+fn find_account<'info>(
+    accounts: &[AccountInfo<'info>],
+    key: &Pubkey,
+) -> Result<AccountInfo<'info>> {
+    accounts
+        .iter()
+        .find(|account| account.key == key)
+        .cloned()
+        .ok_or_else(|| {
+            msg!("Missing account {}", key);
+            error!(ErrorCode::AccountNotEnoughKeys)
+        })
+}
+
+fn to_swap_context<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, crate::cpi::accounts::Swap<'info>>,
+    share_fee_rate: u64,
+    share_fee_receiver: Option<Pubkey>,
+) -> Result<CpiContext<'a, 'b, 'c, 'info, crate::cpi::accounts::Swap<'info>>> {
+    require_eq!(
+        share_fee_receiver.is_some(),
+        share_fee_rate > 0,
+        ErrorCode::AccountNotEnoughKeys
+    );
+    let pool_state = {
+        let data = ctx.accounts.pool_state.try_borrow_data()?;
+        PoolState::try_deserialize(&mut &data[..])?
+    };
+    let remaining_accounts = SwapRemainingAccounts::new(&pool_state, share_fee_receiver)
+        .to_account_infos(&ctx.remaining_accounts)?;
+    Ok(
+        CpiContext::new_with_signer(ctx.program, ctx.accounts, ctx.signer_seeds)
+            .with_remaining_accounts(remaining_accounts),
+    )
+}
+
+/// Invokes `buy_exact_in` with the remaining accounts in the order the program expects.
+/// The share fee receiver, the system program and the fee vaults must be in
+/// `ctx.remaining_accounts`, in any order.
+///
+/// # Arguments
+///
+/// * `ctx` - The context of accounts
+/// * `amount_in` - Amount of quote token to purchase
+/// * `minimum_amount_out` - Minimum amount of base token to receive (slippage protection)
+/// * `share_fee_rate` - Fee rate for the share
+/// * `share_fee_receiver` - The share fee receiver, required if and only if `share_fee_rate > 0`
+///
+pub fn buy_exact_in<'a, 'b, 'c, 'info>(
+    ctx: CpiContext<'a, 'b, 'c, 'info, crate::cpi::accounts::Swap<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    share_fee_rate: u64,
+    share_fee_receiver: Option<Pubkey>,
+) -> Result<()> {
+    crate::cpi::buy_exact_in(
+        to_swap_context(ctx, share_fee_rate, share_fee_receiver)?,
+        amount_in,
+        minimum_amount_out,
+        share_fee_rate,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn new(key: Pubkey) -> Self {
+            Self {
+                key,
+                owner: Pubkey::default(),
+                lamports: 0,
+                data: Vec::new(),
+            }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                true,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    fn pool_state() -> PoolState {
+        let mut pool_state = PoolState::deserialize(&mut &[0u8; PoolState::LEN - 8][..]).unwrap();
+        pool_state.platform_config = Pubkey::new_unique();
+        pool_state.quote_mint = Pubkey::new_unique();
+        pool_state.creator = Pubkey::new_unique();
+        pool_state
+    }
+
+    fn keys(metas: &[AccountMeta]) -> Vec<Pubkey> {
+        metas.iter().map(|meta| meta.pubkey).collect()
+    }
+
+    #[test]
+    fn swap_remaining_account_metas() {
+        let pool_state = pool_state();
+        let platform_fee_vault =
+            platform_fee_vault(&pool_state.platform_config, &pool_state.quote_mint);
+        let creator_fee_vault = creator_fee_vault(&pool_state.creator, &pool_state.quote_mint);
+
+        let metas = SwapRemainingAccounts::new(&pool_state, None).to_account_metas();
+        assert_eq!(
+            keys(&metas),
+            [system_program::ID, platform_fee_vault, creator_fee_vault]
+        );
+        assert_eq!(
+            metas
+                .iter()
+                .map(|meta| meta.is_writable)
+                .collect::<Vec<_>>(),
+            [false, true, true]
+        );
+        assert!(metas.iter().all(|meta| !meta.is_signer));
+
+        let share_fee_receiver = Pubkey::new_unique();
+        let metas =
+            SwapRemainingAccounts::new(&pool_state, Some(share_fee_receiver)).to_account_metas();
+        assert_eq!(
+            keys(&metas),
+            [
+                share_fee_receiver,
+                system_program::ID,
+                platform_fee_vault,
+                creator_fee_vault
+            ]
+        );
+        assert!(metas[0].is_writable);
+    }
+
+    #[test]
+    fn swap_remaining_account_infos() {
+        let share_fee_receiver = Pubkey::new_unique();
+        let remaining_accounts =
+            SwapRemainingAccounts::new(&pool_state(), Some(share_fee_receiver));
+        let mut accounts: Vec<TestAccount> = keys(&remaining_accounts.to_account_metas())
+            .into_iter()
+            .rev()
+            .chain([Pubkey::new_unique()])
+            .map(TestAccount::new)
+            .collect();
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+
+        let picked = remaining_accounts.to_account_infos(&infos).unwrap();
+        assert_eq!(
+            picked.iter().map(|info| *info.key).collect::<Vec<_>>(),
+            keys(&remaining_accounts.to_account_metas())
+        );
+        assert_eq!(
+            remaining_accounts
+                .to_account_infos(&infos[1..])
+                .unwrap_err(),
+            ErrorCode::AccountNotEnoughKeys.into()
+        );
+    }
+
+    #[test]
+    fn swap_context_requires_share_fee_receiver_with_share_fee_rate() {
+        let pool_state = pool_state();
+        let mut pool = TestAccount::new(Pubkey::new_unique());
+        pool.owner = crate::id();
+        pool_state.try_serialize(&mut pool.data).unwrap();
+        let share_fee_receiver = Pubkey::new_unique();
+        let remaining_keys = keys(
+            &SwapRemainingAccounts::new(&pool_state, Some(share_fee_receiver)).to_account_metas(),
+        );
+        let mut accounts: Vec<TestAccount> = (0..14)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect();
+        let mut remaining: Vec<TestAccount> = remaining_keys
+            .iter()
+            .copied()
+            .map(TestAccount::new)
+            .collect();
+
+        let pool_info = pool.info();
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        let remaining_infos: Vec<AccountInfo> =
+            remaining.iter_mut().map(TestAccount::info).collect();
+        let ctx = || {
+            CpiContext::new(
+                infos[0].clone(),
+                crate::cpi::accounts::Swap {
+                    payer: infos[1].clone(),
+                    authority: infos[2].clone(),
+                    global_config: infos[3].clone(),
+                    platform_config: infos[4].clone(),
+                    pool_state: pool_info.clone(),
+                    user_base_token: infos[5].clone(),
+                    user_quote_token: infos[6].clone(),
+                    base_vault: infos[7].clone(),
+                    quote_vault: infos[8].clone(),
+                    base_token_mint: infos[9].clone(),
+                    quote_token_mint: infos[10].clone(),
+                    base_token_program: infos[11].clone(),
+                    quote_token_program: infos[12].clone(),
+                    event_authority: infos[13].clone(),
+                    program: infos[0].clone(),
+                },
+            )
+            .with_remaining_accounts(remaining_infos.iter().rev().cloned().collect())
+        };
+
+        for (share_fee_rate, share_fee_receiver) in [(0, Some(share_fee_receiver)), (100, None)] {
+            assert_eq!(
+                to_swap_context(ctx(), share_fee_rate, share_fee_receiver).err(),
+                Some(ErrorCode::AccountNotEnoughKeys.into())
+            );
+        }
+        let swap_ctx = to_swap_context(ctx(), 100, Some(share_fee_receiver)).unwrap();
+        assert_eq!(
+            swap_ctx
+                .remaining_accounts
+                .iter()
+                .map(|info| *info.key)
+                .collect::<Vec<_>>(),
+            remaining_keys
+        );
+        // Without a share fee the receiver is left out even if present
+        let swap_ctx = to_swap_context(ctx(), 0, None).unwrap();
+        assert_eq!(swap_ctx.remaining_accounts.len(), 3);
+    }
+}
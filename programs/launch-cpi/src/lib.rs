@@ -1,9 +1,16 @@
 #![allow(unused)]
 
 pub mod context;
+pub mod curve;
+pub mod events;
+pub mod fees;
+pub mod instructions;
+pub mod pda;
 pub mod states;
 
 pub use context::*;
+pub use events::*;
+pub use pda::*;
 pub use states::*;
 
 use anchor_lang::prelude::*;
@@ -189,6 +196,88 @@ pub mod raydium_launchpad {
         Ok(())
     }
 
+    /// Migrate the pool to the AMM once `real_quote` reaches `total_quote_fund_raising`,
+    /// creating the OpenBook market and the amm pool with the raised liquidity.
+    /// Only `GlobalConfig.migrate_to_amm_wallet` can call it, the program rejects any other payer
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `base_lot_size` - The base lot size of the OpenBook market
+    /// * `quote_lot_size` - The quote lot size of the OpenBook market
+    /// * `market_vault_signer_nonce` - The nonce of the market vault signer
+    ///
+    pub fn migrate_to_amm(
+        ctx: Context<MigrateToAmm>,
+        base_lot_size: u64,
+        quote_lot_size: u64,
+        market_vault_signer_nonce: u8,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Migrate the pool to the CP-Swap once `real_quote` reaches `total_quote_fund_raising`,
+    /// creating the cpswap pool with the raised liquidity and locking the lp tokens.
+    /// Only `GlobalConfig.migrate_to_cpswap_wallet` can call it, the program rejects any other payer
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn migrate_to_cpswap(ctx: Context<MigrateToCpswap>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Creates a new global config, only callable by the admin
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `curve_type` - 0: Constant Product Curve, 1: Fixed Price Curve, 2: Linear Price Curve
+    /// * `index` - The index of the config, there may be multiple configs per quote mint and curve type
+    /// * `migrate_fee` - The fee of migrate to amm
+    /// * `trade_fee_rate` - The trade fee rate, denominated in hundredths of a bip (10^-6)
+    ///
+    #[cfg(feature = "admin")]
+    pub fn create_config(
+        ctx: Context<CreateConfig>,
+        curve_type: u8,
+        index: u16,
+        migrate_fee: u64,
+        trade_fee_rate: u64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Updates a field of the global config, only callable by the admin
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `param` - The field to update
+    /// * `value` - The new value
+    ///
+    #[cfg(feature = "admin")]
+    pub fn update_config(ctx: Context<UpdateConfig>, param: u8, value: u64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Collects the accumulated protocol fee of a pool, `pool_state.quote_protocol_fee`
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    #[cfg(feature = "admin")]
+    pub fn collect_fee(ctx: Context<CollectFee>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Collects the migrate fee of a pool, `pool_state.migrate_fee`
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    #[cfg(feature = "admin")]
+    pub fn collect_migrate_fee(ctx: Context<CollectMigrateFee>) -> Result<()> {
+        Ok(())
+    }
+
     /// Create platform config account
     /// # Arguments
     ///
@@ -0,0 +1,154 @@
+//! Program derived addresses of the Raydium launchpad program.
+use crate::{states::*, AUTH_SEED};
+use anchor_lang::prelude::*;
+
+/// Derives the vault authority with seed = [AUTH_SEED]
+pub fn get_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &crate::id())
+}
+
+/// Derives the event authority of the self-CPI events with seed = [b"__event_authority"]
+pub fn get_event_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &crate::id())
+}
+
+/// Derives the global config with seed = [GLOBAL_CONFIG_SEED, quote_mint, curve_type, index]
+pub fn get_global_config_address(quote_mint: &Pubkey, curve_type: u8, index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            GLOBAL_CONFIG_SEED.as_bytes(),
+            quote_mint.as_ref(),
+            &curve_type.to_le_bytes(),
+            &index.to_le_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+/// Derives the pool state with seed = [POOL_SEED, base_mint, quote_mint]
+pub fn get_pool_state_address(base_mint: &Pubkey, quote_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            POOL_SEED.as_bytes(),
+            base_mint.as_ref(),
+            quote_mint.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+/// Derives a pool vault with seed = [POOL_VAULT_SEED, pool_state, mint],
+/// `mint` is the base mint for the base vault and the quote mint for the quote vault
+pub fn get_pool_vault_address(pool_state: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.as_ref(),
+            mint.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+/// Derives the vesting record of a beneficiary with seed = [POOL_VESTING_SEED, pool_state, beneficiary]
+pub fn get_vesting_record_address(pool_state: &Pubkey, beneficiary: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            POOL_VESTING_SEED.as_bytes(),
+            pool_state.as_ref(),
+            beneficiary.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+/// Derives the platform config with seed = [PLATFORM_CONFIG_SEED, platform_admin]
+pub fn get_platform_config_address(platform_admin: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PLATFORM_CONFIG_SEED.as_bytes(), platform_admin.as_ref()],
+        &crate::id(),
+    )
+}
+
+/// Derives the authority of all platform fee vaults with seed = [PLATFORM_FEE_VAULT_AUTH_SEED]
+pub fn get_platform_fee_vault_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PLATFORM_FEE_VAULT_AUTH_SEED.as_bytes()], &crate::id())
+}
+
+/// Derives the authority of all creator fee vaults with seed = [CREATOR_FEE_VAULT_AUTH_SEED]
+pub fn get_creator_fee_vault_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CREATOR_FEE_VAULT_AUTH_SEED.as_bytes()], &crate::id())
+}
+
+/// Derives the platform fee vault of a quote mint with seed = [platform_config, quote_mint]
+pub fn get_platform_fee_vault_address(
+    platform_config: &Pubkey,
+    quote_mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[platform_config.as_ref(), quote_mint.as_ref()],
+        &crate::id(),
+    )
+}
+
+/// Derives the creator fee vault of a quote mint with seed = [creator, quote_mint]
+pub fn get_creator_fee_vault_address(creator: &Pubkey, quote_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[creator.as_ref(), quote_mint.as_ref()], &crate::id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_addresses() {
+        assert_eq!(
+            get_authority_address().0,
+            pubkey!("WLHv2UAZm6z4KyaaELi5pjdbJh6RESMva1Rnn8pJVVh")
+        );
+        assert_eq!(
+            get_event_authority_address().0,
+            pubkey!("2DPAtwB8L12vrMRExbLuyGnC7n2J5LNoZQSejeQGpwkr")
+        );
+        assert_eq!(
+            get_global_config_address(&anchor_spl::token::spl_token::native_mint::ID, 0, 0).0,
+            pubkey!("6s1xP3hpbAfFoNtUNF8mfHsjr2Bd97JxFJRWLbL6aHuX")
+        );
+    }
+
+    #[test]
+    fn pool_addresses() {
+        let base_mint = Pubkey::new_unique();
+        let quote_mint = Pubkey::new_unique();
+        let (pool_state, bump) = get_pool_state_address(&base_mint, &quote_mint);
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[b"pool", base_mint.as_ref(), quote_mint.as_ref(), &[bump]],
+                &crate::id()
+            )
+            .unwrap(),
+            pool_state
+        );
+        // The mints are not interchangeable
+        assert_ne!(
+            get_pool_state_address(&quote_mint, &base_mint).0,
+            pool_state
+        );
+        assert_ne!(
+            get_pool_vault_address(&pool_state, &base_mint).0,
+            get_pool_vault_address(&pool_state, &quote_mint).0
+        );
+
+        // Fee vaults are seeded by the owner key only, so a platform config and a creator
+        // with the same key share a vault
+        let owner = Pubkey::new_unique();
+        assert_eq!(
+            get_platform_fee_vault_address(&owner, &quote_mint),
+            get_creator_fee_vault_address(&owner, &quote_mint)
+        );
+        assert_ne!(
+            get_platform_fee_vault_authority_address().0,
+            get_creator_fee_vault_authority_address().0
+        );
+    }
+}
@@ -11,6 +11,8 @@ pub const PLATFORM_FEE_VAULT_AUTH_SEED: &str = "platform_fee_vault_auth_seed";
 pub const CREATOR_FEE_VAULT_AUTH_SEED: &str = "creator_fee_vault_auth_seed";
 
 pub const PLATFORM_CONFIG_SEED: &str = "platform_config";
+/// Denominator of all rates and scales, in hundredths of a bip (10^-6)
+pub const RATE_DENOMINATOR_VALUE: u64 = 1_000_000;
 pub const NAME_SIZE: usize = 64;
 pub const WEB_SIZE: usize = 256;
 pub const IMG_SIZE: usize = 256;
@@ -57,33 +59,98 @@ pub struct GlobalConfig {
     pub padding: [u64; 16],
 }
 
+impl GlobalConfig {
+    pub const LEN: usize = 8 + 8 + 1 + 2 + 8 * 8 + 32 * 5 + 8 * 16;
+}
+
 /// Represents the different states a pool can be in
 /// * Fund - Initial state where pool is accepting funds
 /// * Migrate - Pool funding has ended and waiting for migration
 /// * Trade - Pool migration is complete and amm trading is enabled
-#[derive(PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
 pub enum PoolStatus {
     Fund,
     Migrate,
     Trade,
 }
 
-#[derive(PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+impl TryFrom<u8> for PoolStatus {
+    type Error = Error;
+
+    fn try_from(status: u8) -> Result<Self> {
+        match status {
+            0 => Ok(PoolStatus::Fund),
+            1 => Ok(PoolStatus::Migrate),
+            2 => Ok(PoolStatus::Trade),
+            _ => err!(ErrorCode::AccountDidNotDeserialize),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
 pub enum MigrateType {
     AMM,
     CPSWAP,
 }
 
+impl TryFrom<u8> for MigrateType {
+    type Error = Error;
+
+    fn try_from(migrate_type: u8) -> Result<Self> {
+        match migrate_type {
+            0 => Ok(MigrateType::AMM),
+            1 => Ok(MigrateType::CPSWAP),
+            _ => err!(ErrorCode::AccountDidNotDeserialize),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TokenProgramFlag {
     SPLTokenProgram,
     TokenProgram2022,
 }
 
+impl TokenProgramFlag {
+    /// The token program id
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            TokenProgramFlag::SPLTokenProgram => anchor_spl::token::ID,
+            TokenProgramFlag::TokenProgram2022 => anchor_spl::token_2022::ID,
+        }
+    }
+}
+
+impl TryFrom<u8> for TokenProgramFlag {
+    type Error = Error;
+
+    fn try_from(flag: u8) -> Result<Self> {
+        match flag {
+            0 => Ok(TokenProgramFlag::SPLTokenProgram),
+            1 => Ok(TokenProgramFlag::TokenProgram2022),
+            _ => err!(ErrorCode::AccountDidNotDeserialize),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TokenProgramBit {
     BaseTokenProgram,
     QuoteTokenProgram,
 }
 
+impl TryFrom<u8> for TokenProgramBit {
+    type Error = Error;
+
+    fn try_from(bit: u8) -> Result<Self> {
+        match bit {
+            0 => Ok(TokenProgramBit::BaseTokenProgram),
+            1 => Ok(TokenProgramBit::QuoteTokenProgram),
+            _ => err!(ErrorCode::AccountDidNotDeserialize),
+        }
+    }
+}
+
 /// migrate to cpmm, creator fee on quote token or both token
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub enum AmmCreatorFeeOn {
@@ -197,6 +264,51 @@ pub struct PoolState {
 
 impl PoolState {
     pub const LEN: usize = 8 + 8 + 1 * 5 + 8 * 10 + 7 * 32 + VestingSchedule::LEN + 8 * 8;
+
+    /// Current status of the pool
+    pub fn status(&self) -> Result<PoolStatus> {
+        PoolStatus::try_from(self.status)
+    }
+
+    /// Where the pool migrates once the fundraising ends
+    pub fn migrate_type(&self) -> Result<MigrateType> {
+        MigrateType::try_from(self.migrate_type)
+    }
+
+    /// Whether the base or quote token belongs to the token 2022 program
+    pub fn is_token_2022(&self, bit: TokenProgramBit) -> bool {
+        self.token_program_flag & (1 << bit as u8) != 0
+    }
+
+    /// The token program of the base token
+    pub fn base_token_program(&self) -> Pubkey {
+        self.token_program(TokenProgramBit::BaseTokenProgram)
+    }
+
+    /// The token program of the quote token
+    pub fn quote_token_program(&self) -> Pubkey {
+        self.token_program(TokenProgramBit::QuoteTokenProgram)
+    }
+
+    /// Ratio of `real_quote` to `total_quote_fund_raising` in `RATE_DENOMINATOR_VALUE`
+    /// units, rounded down and capped at `RATE_DENOMINATOR_VALUE`.
+    /// The pool can be migrated once it reaches `RATE_DENOMINATOR_VALUE`.
+    pub fn funding_progress(&self) -> u64 {
+        if self.total_quote_fund_raising == 0 {
+            return 0;
+        }
+        let progress = u128::from(self.real_quote) * u128::from(RATE_DENOMINATOR_VALUE)
+            / u128::from(self.total_quote_fund_raising);
+        progress.min(u128::from(RATE_DENOMINATOR_VALUE)) as u64
+    }
+
+    fn token_program(&self, bit: TokenProgramBit) -> Pubkey {
+        if self.is_token_2022(bit) {
+            TokenProgramFlag::TokenProgram2022.program_id()
+        } else {
+            TokenProgramFlag::SPLTokenProgram.program_id()
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, AnchorSerialize, AnchorDeserialize)]
@@ -215,6 +327,35 @@ pub struct VestingSchedule {
 
 impl VestingSchedule {
     pub const LEN: usize = 8 + 8 + 8 + 8 + 8;
+
+    /// Time the linear unlock begins, `None` until the fundraising ends and `start_time` is set
+    pub fn unlock_start_time(&self) -> Option<u64> {
+        if self.start_time == 0 {
+            return None;
+        }
+        self.start_time.checked_add(self.cliff_period)
+    }
+
+    /// Time all the shares are unlocked, `None` until the fundraising ends
+    pub fn fully_vested_at(&self) -> Option<u64> {
+        self.unlock_start_time()?.checked_add(self.unlock_period)
+    }
+
+    /// Amount of `token_share_amount` unlocked at `now`, rounded down
+    pub fn unlocked_amount_at(&self, token_share_amount: u64, now: u64) -> u64 {
+        let Some(unlock_start_time) = self.unlock_start_time() else {
+            return 0;
+        };
+        if now <= unlock_start_time {
+            return 0;
+        }
+        let elapsed = now - unlock_start_time;
+        if elapsed >= self.unlock_period {
+            return token_share_amount;
+        }
+        (u128::from(token_share_amount) * u128::from(elapsed) / u128::from(self.unlock_period))
+            as u64
+    }
 }
 
 #[account]
@@ -255,6 +396,27 @@ pub struct PlatformConfig {
 impl PlatformConfig {
     pub const MIN_LEN: usize =
         8 + 8 + 32 * 2 + 8 * 4 + NAME_SIZE + WEB_SIZE + IMG_SIZE + 32 + 8 + 32 + 180 + 4;
+
+    /// The platform name, `None` if it is not valid UTF-8
+    pub fn name_str(&self) -> Option<&str> {
+        str_from_buffer(&self.name)
+    }
+
+    /// The platform website, `None` if it is not valid UTF-8
+    pub fn web_str(&self) -> Option<&str> {
+        str_from_buffer(&self.web)
+    }
+
+    /// The platform img link, `None` if it is not valid UTF-8
+    pub fn img_str(&self) -> Option<&str> {
+        str_from_buffer(&self.img)
+    }
+}
+
+/// The string of a zero padded buffer
+fn str_from_buffer(buffer: &[u8]) -> Option<&str> {
+    let len = buffer.iter().position(|b| *b == 0).unwrap_or(buffer.len());
+    std::str::from_utf8(&buffer[..len]).ok()
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -325,6 +487,19 @@ pub struct VestingRecord {
 
 impl VestingRecord {
     pub const LEN: usize = 8 + 8 + 32 + 32 + 8 + 8 + 8 * 8;
+
+    /// Amount `claim_vested_token` would transfer at `now`, unlocked linearly
+    /// over `unlock_period` once `cliff_period` has passed since `start_time`
+    pub fn claimable_at(&self, vesting_schedule: &VestingSchedule, now: u64) -> u64 {
+        vesting_schedule
+            .unlocked_amount_at(self.token_share_amount, now)
+            .saturating_sub(self.claimed_amount)
+    }
+
+    /// Time all the shares of the record are unlocked, `None` until the fundraising ends
+    pub fn fully_vested_at(&self, vesting_schedule: &VestingSchedule) -> Option<u64> {
+        vesting_schedule.fully_vested_at()
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
@@ -356,7 +531,7 @@ pub enum CurveParams {
     Linear { data: LinearCurve },
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct VestingParams {
     // total amount of tokens to be unlocked
     pub total_locked_amount: u64,
@@ -372,7 +547,7 @@ pub struct VestingParams {
 /// * `name` - Name of the token
 /// * `symbol` - Symbol/ticker of the token
 /// * `uri` - URI pointing to token metadata
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct MintParams {
     pub decimals: u8,
     pub name: String,
@@ -407,6 +582,32 @@ pub struct MigrateNftInfo {
     pub burn_scale: u64,
 }
 
+impl MigrateNftInfo {
+    /// Creates the scales, which must sum to `RATE_DENOMINATOR_VALUE`
+    pub fn new(platform_scale: u64, creator_scale: u64, burn_scale: u64) -> Result<Self> {
+        let migrate_nft_info = Self {
+            platform_scale,
+            creator_scale,
+            burn_scale,
+        };
+        migrate_nft_info.validate()?;
+        Ok(migrate_nft_info)
+    }
+
+    /// Checks that the scales sum to `RATE_DENOMINATOR_VALUE`
+    pub fn validate(&self) -> Result<()> {
+        let total_scale = self
+            .platform_scale
+            .checked_add(self.creator_scale)
+            .and_then(|scale| scale.checked_add(self.burn_scale))
+            .ok_or(ErrorCode::InvalidNumericConversion)?;
+        require_eq!(total_scale, RATE_DENOMINATOR_VALUE);
+        Ok(())
+    }
+}
+
+/// Represents all the fields of a platform config, to update them at once
+/// with `PlatformConfigParam::AllInfo`
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct PlatformConfigInfo {
     fee_wallet: Pubkey,
@@ -418,6 +619,64 @@ pub struct PlatformConfigInfo {
     img: String,
 }
 
+impl PlatformConfigInfo {
+    /// Creates the platform config info, the scales of `migrate_nft_info`
+    /// must sum to `RATE_DENOMINATOR_VALUE`, `fee_rate` must not exceed it and
+    /// `name`, `web` and `img` must fit in `NAME_SIZE`, `WEB_SIZE` and `IMG_SIZE` bytes
+    pub fn new(
+        fee_wallet: Pubkey,
+        nft_wallet: Pubkey,
+        migrate_nft_info: MigrateNftInfo,
+        fee_rate: u64,
+        name: String,
+        web: String,
+        img: String,
+    ) -> Result<Self> {
+        migrate_nft_info.validate()?;
+        require_gte!(RATE_DENOMINATOR_VALUE, fee_rate);
+        require_gte!(NAME_SIZE, name.len());
+        require_gte!(WEB_SIZE, web.len());
+        require_gte!(IMG_SIZE, img.len());
+        Ok(Self {
+            fee_wallet,
+            nft_wallet,
+            migrate_nft_info,
+            fee_rate,
+            name,
+            web,
+            img,
+        })
+    }
+
+    pub fn fee_wallet(&self) -> Pubkey {
+        self.fee_wallet
+    }
+
+    pub fn nft_wallet(&self) -> Pubkey {
+        self.nft_wallet
+    }
+
+    pub fn migrate_nft_info(&self) -> &MigrateNftInfo {
+        &self.migrate_nft_info
+    }
+
+    pub fn fee_rate(&self) -> u64 {
+        self.fee_rate
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn web(&self) -> &str {
+        &self.web
+    }
+
+    pub fn img(&self) -> &str {
+        &self.img
+    }
+}
+
 /// Represents the parameters for initializing a platform config account
 /// # Fields
 /// * `migrate_nft_info` - The platform configures liquidity info during migration(Only support MigrateType::CPSWAP)
@@ -436,18 +695,152 @@ pub struct PlatformParams {
 
 /// Platform fee vault addres
 pub fn platform_fee_vault(platform_config: &Pubkey, quote_token_mint: &Pubkey) -> Pubkey {
-    let (expect_platform_fee_vault, bump) = Pubkey::find_program_address(
-        &[platform_config.as_ref(), quote_token_mint.as_ref()],
-        &crate::id(),
-    );
-    expect_platform_fee_vault
+    crate::pda::get_platform_fee_vault_address(platform_config, quote_token_mint).0
 }
 
 /// Creator fee vault address
 pub fn creator_fee_vault(creator: &Pubkey, quote_token_mint: &Pubkey) -> Pubkey {
-    let (expect_creator_fee_vault, bump) = Pubkey::find_program_address(
-        &[creator.as_ref(), quote_token_mint.key().as_ref()],
-        &crate::id(),
-    );
-    expect_creator_fee_vault
+    crate::pda::get_creator_fee_vault_address(creator, quote_token_mint).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_state() -> PoolState {
+        PoolState::deserialize(&mut &[0u8; PoolState::LEN - 8][..]).unwrap()
+    }
+
+    #[test]
+    fn token_program_bits() {
+        assert_eq!(
+            TokenProgramBit::try_from(1).unwrap(),
+            TokenProgramBit::QuoteTokenProgram
+        );
+        assert_eq!(
+            TokenProgramBit::try_from(2).unwrap_err(),
+            ErrorCode::AccountDidNotDeserialize.into()
+        );
+
+        let mut pool_state = pool_state();
+        assert!(!pool_state.is_token_2022(TokenProgramBit::BaseTokenProgram));
+        assert_eq!(pool_state.base_token_program(), anchor_spl::token::ID);
+        pool_state.token_program_flag = 0b01;
+        assert!(pool_state.is_token_2022(TokenProgramBit::BaseTokenProgram));
+        assert!(!pool_state.is_token_2022(TokenProgramBit::QuoteTokenProgram));
+        assert_eq!(pool_state.base_token_program(), anchor_spl::token_2022::ID);
+        assert_eq!(pool_state.quote_token_program(), anchor_spl::token::ID);
+        pool_state.token_program_flag = 0b10;
+        assert_eq!(pool_state.base_token_program(), anchor_spl::token::ID);
+        assert_eq!(pool_state.quote_token_program(), anchor_spl::token_2022::ID);
+    }
+
+    #[test]
+    fn funding_progress() {
+        let mut pool_state = pool_state();
+        assert_eq!(pool_state.funding_progress(), 0);
+        pool_state.total_quote_fund_raising = 85_000_000_000;
+        pool_state.real_quote = 1_000_000_000;
+        // 1.17647...%, rounded down
+        assert_eq!(pool_state.funding_progress(), 11_764);
+        pool_state.real_quote = 84_999_999_999;
+        assert_eq!(pool_state.funding_progress(), 999_999);
+        pool_state.real_quote = 85_000_000_000;
+        assert_eq!(pool_state.funding_progress(), RATE_DENOMINATOR_VALUE);
+        pool_state.real_quote = u64::MAX;
+        assert_eq!(pool_state.funding_progress(), RATE_DENOMINATOR_VALUE);
+    }
+
+    fn vesting_schedule() -> VestingSchedule {
+        VestingSchedule {
+            total_locked_amount: 1000,
+            cliff_period: 10,
+            unlock_period: 100,
+            start_time: 1000,
+            allocated_share_amount: 1000,
+        }
+    }
+
+    fn vesting_record(claimed_amount: u64) -> VestingRecord {
+        VestingRecord {
+            epoch: 0,
+            pool: Pubkey::default(),
+            beneficiary: Pubkey::default(),
+            claimed_amount,
+            token_share_amount: 1000,
+            padding: [0; 8],
+        }
+    }
+
+    #[test]
+    fn vesting_before_fundraising_ends() {
+        let vesting_schedule = VestingSchedule {
+            start_time: 0,
+            ..vesting_schedule()
+        };
+        assert_eq!(vesting_schedule.unlock_start_time(), None);
+        assert_eq!(vesting_schedule.fully_vested_at(), None);
+        assert_eq!(vesting_schedule.unlocked_amount_at(1000, u64::MAX), 0);
+        assert_eq!(
+            vesting_record(0).claimable_at(&vesting_schedule, u64::MAX),
+            0
+        );
+    }
+
+    #[test]
+    fn vesting_unlocked_amount_at() {
+        let vesting_schedule = vesting_schedule();
+        assert_eq!(vesting_schedule.unlock_start_time(), Some(1010));
+        assert_eq!(vesting_schedule.fully_vested_at(), Some(1110));
+        // Before and at the cliff
+        assert_eq!(vesting_schedule.unlocked_amount_at(1000, 1000), 0);
+        assert_eq!(vesting_schedule.unlocked_amount_at(1000, 1009), 0);
+        assert_eq!(vesting_schedule.unlocked_amount_at(1000, 1010), 0);
+        // Mid unlock, rounded down
+        assert_eq!(vesting_schedule.unlocked_amount_at(1000, 1011), 10);
+        assert_eq!(vesting_schedule.unlocked_amount_at(999, 1060), 499);
+        // Fully vested
+        assert_eq!(vesting_schedule.unlocked_amount_at(1000, 1110), 1000);
+        assert_eq!(vesting_schedule.unlocked_amount_at(1000, u64::MAX), 1000);
+    }
+
+    #[test]
+    fn vesting_claimable_at() {
+        let vesting_schedule = vesting_schedule();
+        let vesting_record = vesting_record(100);
+        assert_eq!(vesting_record.claimable_at(&vesting_schedule, 1005), 0);
+        assert_eq!(vesting_record.claimable_at(&vesting_schedule, 1010), 0);
+        // Less unlocked than claimed
+        assert_eq!(vesting_record.claimable_at(&vesting_schedule, 1015), 0);
+        assert_eq!(vesting_record.claimable_at(&vesting_schedule, 1060), 400);
+        assert_eq!(vesting_record.claimable_at(&vesting_schedule, 1110), 900);
+        assert_eq!(vesting_record.claimable_at(&vesting_schedule, 5000), 900);
+        assert_eq!(
+            vesting_record.fully_vested_at(&vesting_schedule),
+            Some(1110)
+        );
+    }
+
+    #[test]
+    fn platform_config_info_new() {
+        let new = |fee_rate: u64, name: usize, web: usize, img: usize| {
+            PlatformConfigInfo::new(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                MigrateNftInfo::new(0, 0, RATE_DENOMINATOR_VALUE).unwrap(),
+                fee_rate,
+                "n".repeat(name),
+                "w".repeat(web),
+                "i".repeat(img),
+            )
+        };
+        let info = new(RATE_DENOMINATOR_VALUE, NAME_SIZE, WEB_SIZE, IMG_SIZE).unwrap();
+        assert_eq!(info.fee_rate(), RATE_DENOMINATOR_VALUE);
+        assert_eq!(info.name().len(), NAME_SIZE);
+        assert!(new(RATE_DENOMINATOR_VALUE + 1, 0, 0, 0).is_err());
+        assert!(new(0, NAME_SIZE + 1, 0, 0).is_err());
+        assert!(new(0, 0, WEB_SIZE + 1, 0).is_err());
+        assert!(new(0, 0, 0, IMG_SIZE + 1).is_err());
+        assert!(MigrateNftInfo::new(1, 0, RATE_DENOMINATOR_VALUE).is_err());
+    }
 }